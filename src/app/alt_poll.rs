@@ -1,44 +1,67 @@
 use crate::platform;
 use gpui::{AnyWindowHandle, AppContext, AsyncApp, WeakEntity};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const ALT_POLL_INTERVAL_MS: u64 = 50;
 
-/// Start a new Alt-key polling task for HoldToSwitch mode.
+/// Start a new modifier polling task (`action_modifier`, Alt by default) for HoldToSwitch mode.
 /// Drops any previous task (which auto-cancels it).
 pub(crate) fn start(
     app: &mut super::AltTabApp,
     window_handle: AnyWindowHandle,
     cx: &mut gpui::Context<super::AltTabApp>,
 ) {
-    let list = app.delegate.clone();
+    let debounce = Duration::from_millis(app.activation_debounce_ms);
+    let min_visible = Duration::from_millis(app.min_visible_ms);
+    let modifier = app.action_modifier;
+    let opened_at = Instant::now();
     app.alt_was_held = true;
     app._alt_poll_task = Some(cx.spawn(
         move |this: WeakEntity<super::AltTabApp>, cx: &mut AsyncApp| {
             let mut cx = cx.clone();
             async move {
                 eprintln!("[alt-tab/hold] X11 modifier poll task started");
-                cx.background_executor()
-                    .timer(Duration::from_millis(50))
-                    .await;
+                // The first check runs right at open: a release caught only at the
+                // first interval would already be past `min_visible`.
                 loop {
-                    cx.background_executor()
-                        .timer(Duration::from_millis(ALT_POLL_INTERVAL_MS))
-                        .await;
-                    let alt_held = platform::is_modifier_held();
+                    let alt_held = platform::is_modifier_held(modifier);
 
+                    if !alt_held && opened_at.elapsed() < debounce {
+                        eprintln!(
+                            "[alt-tab/hold] X11 poll: Alt released within debounce — cancelling"
+                        );
+                        let this = this.clone();
+                        let _ = cx.update_window(window_handle, |_root, window, cx| {
+                            if let Some(entity) = this.upgrade() {
+                                entity.update(cx, |app: &mut super::AltTabApp, cx| {
+                                    app.dismiss(window, cx);
+                                });
+                            }
+                        });
+                        break;
+                    }
                     if !alt_held {
                         eprintln!(
                             "[alt-tab/hold] X11 poll: Alt released — activating selected"
                         );
-                        let list = list.clone();
+                        // Hold the picker for the rest of min_visible so a quick release doesn't flash it.
+                        let remaining = min_visible.saturating_sub(opened_at.elapsed());
+                        if !remaining.is_zero() {
+                            cx.background_executor().timer(remaining).await;
+                        }
+                        let this = this.clone();
                         let _ = cx.update_window(window_handle, |_root, window, cx| {
-                            list.update(cx, |s, _cx| {
-                                s.activate_selected(window);
-                            });
+                            if let Some(entity) = this.upgrade() {
+                                entity.update(cx, |app: &mut super::AltTabApp, cx| {
+                                    app.activate_selected(window, cx);
+                                });
+                            }
                         });
                         break;
                     }
+                    cx.background_executor()
+                        .timer(Duration::from_millis(ALT_POLL_INTERVAL_MS))
+                        .await;
                 }
 
                 // Clear the task reference so subsequent Show requests know we're fully closed
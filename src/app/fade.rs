@@ -0,0 +1,58 @@
+use super::{AltTabApp, PICKER_VISIBLE};
+use crate::config::AltTabConfig;
+use crate::platform;
+use gpui::{AsyncApp, Window, WeakEntity};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+const FADE_FRAME_MS: u64 = 16;
+
+/// `close_fade_ms` to use for this open: none when the OS asks for reduced
+/// motion. Resolved at open since the check may shell out.
+pub(crate) fn configured_ms(config: &AltTabConfig) -> u64 {
+    match config.display.close_fade_ms {
+        0 => 0,
+        _ if platform::prefers_reduced_motion() => 0,
+        ms => ms,
+    }
+}
+
+/// Fade the picker out over `close_fade_ms`, then hide it.
+/// `PICKER_VISIBLE` drops immediately so background loops stop right away.
+pub(crate) fn start(app: &mut AltTabApp, window: &mut Window, cx: &mut gpui::Context<AltTabApp>) {
+    PICKER_VISIBLE.store(false, Ordering::Relaxed);
+    if app.close_fade_ms == 0 {
+        platform::dismiss_picker(window);
+        return;
+    }
+    let duration = Duration::from_millis(app.close_fade_ms);
+    let window_handle = window.window_handle();
+    app.closing_since = Some(Instant::now());
+    app._fade_task = Some(cx.spawn(
+        move |this: WeakEntity<AltTabApp>, cx: &mut AsyncApp| {
+            let mut cx = cx.clone();
+            async move {
+                let started = Instant::now();
+                while started.elapsed() < duration {
+                    cx.background_executor()
+                        .timer(Duration::from_millis(FADE_FRAME_MS))
+                        .await;
+                    let _ = this.update(&mut cx, |_, cx| cx.notify());
+                }
+                let _ = cx.update_window(window_handle, |_root, window, cx| {
+                    platform::dismiss_picker(window);
+                    if let Some(entity) = this.upgrade() {
+                        entity.update(cx, |app, _cx| app.closing_since = None);
+                    }
+                });
+            }
+        },
+    ));
+}
+
+/// Current fade opacity, or `None` when no fade is running.
+pub(crate) fn opacity(app: &AltTabApp) -> Option<f32> {
+    let since = app.closing_since?;
+    let total = app.close_fade_ms.max(1) as f32;
+    Some((1.0 - since.elapsed().as_millis() as f32 / total).clamp(0.0, 1.0))
+}
@@ -0,0 +1,38 @@
+use super::{AltTabApp, PICKER_VISIBLE};
+use crate::config::ActionMode;
+use gpui::{AsyncApp, WeakEntity, Window};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Restart the `auto_dismiss_ms` countdown; the picker dismisses itself when it
+/// runs out. Replacing the task cancels the previous countdown. Hold-to-switch
+/// never gets one since releasing the modifier already closes the picker.
+pub(crate) fn restart(app: &mut AltTabApp, window: &mut Window, cx: &mut gpui::Context<AltTabApp>) {
+    app._idle_task = None;
+    let Some(timeout) = app.auto_dismiss_ms.filter(|ms| *ms > 0) else {
+        return;
+    };
+    if app.action_mode == ActionMode::HoldToSwitch {
+        return;
+    }
+    let window_handle = window.window_handle();
+    app._idle_task = Some(cx.spawn(
+        move |this: WeakEntity<AltTabApp>, cx: &mut AsyncApp| {
+            let mut cx = cx.clone();
+            async move {
+                cx.background_executor()
+                    .timer(Duration::from_millis(timeout))
+                    .await;
+                // Already hidden (activated or cancelled) since the last restart.
+                if !PICKER_VISIBLE.load(Ordering::Relaxed) {
+                    return;
+                }
+                let _ = cx.update_window(window_handle, |_root, window, cx| {
+                    if let Some(entity) = this.upgrade() {
+                        entity.update(cx, |app, cx| app.dismiss(window, cx));
+                    }
+                });
+            }
+        },
+    ));
+}
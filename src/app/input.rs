@@ -1,14 +1,241 @@
-use super::{AltTabApp, PICKER_VISIBLE};
-use crate::layout::rendered_column_count;
+use super::AltTabApp;
+use crate::config::{EmptyEnterAction, LayoutMode, QuickSelect};
+use crate::delegate::shortcuts::{hint_keys, home_row_hints, match_hint, shortcut_index, HintMatch};
+use crate::layout::{picker_dimensions, rendered_column_count, Chrome};
 use crate::platform;
-use gpui::{Context, Window};
-use std::sync::atomic::Ordering;
+use gpui::{px, size, AsyncApp, Context, WeakEntity, Window};
 
 fn selected_window_id(this: &AltTabApp, cx: &Context<AltTabApp>) -> Option<u32> {
-    this.delegate
+    this.delegate.read(cx).selected_window_id()
+}
+
+/// Re-enumerate in the background and swap in the list with minimized windows
+/// shown or hidden, keeping the current selection where possible.
+fn toggle_minimized(this: &mut AltTabApp, cx: &mut Context<AltTabApp>) {
+    let (show_minimized, include_other_spaces, include_desktop, excluded, group, monitor) =
+        this.delegate.update(cx, |s, _cx| {
+            s.show_minimized = !s.show_minimized;
+            (
+                s.show_minimized,
+                s.include_other_spaces,
+                s.include_desktop,
+                s.excluded_window,
+                s.group_filter.clone(),
+                s.monitor_filter.clone(),
+            )
+        });
+    let selected_id = selected_window_id(this, cx);
+    cx.spawn(async move |this: WeakEntity<AltTabApp>, cx: &mut AsyncApp| {
+        let windows: Vec<_> = cx
+            .background_executor()
+            .spawn(async move { platform::get_open_windows(include_other_spaces, include_desktop) })
+            .await
+            .into_iter()
+            .filter(|w| show_minimized || !w.is_minimized)
+            .filter(|w| Some(w.id) != excluded)
+            .filter(|w| group.as_ref().map_or(true, |g| g.matches(&w.app_name, &w.title)))
+            .filter(|w| monitor.as_ref().map_or(true, |f| f.keeps((w.x, w.y, w.width, w.height))))
+            .collect();
+        let _ = this.update(cx, |app, cx| {
+            app.delegate.update(cx, |s, cx| {
+                s.set_windows(windows, false);
+                if let Some(id) = selected_id {
+                    s.select_window_id(id);
+                }
+                cx.notify();
+            });
+            cx.notify();
+        });
+    })
+    .detach();
+}
+
+/// Columns arrow keys navigate in; the list layout is a single column.
+fn nav_columns(this: &AltTabApp, window: &Window, cx: &Context<AltTabApp>) -> usize {
+    let d = this.delegate.read(cx);
+    match this.layout_mode {
+        LayoutMode::Grid => rendered_column_count(window, d.windows.len(), d.card_scale),
+        LayoutMode::List => 1,
+    }
+}
+
+/// Switch between grid and list and resize the picker to fit. The selection
+/// lives on the delegate, so it carries over unchanged.
+fn toggle_layout(this: &mut AltTabApp, window: &mut Window, cx: &mut Context<AltTabApp>) {
+    this.layout_mode = this.layout_mode.toggled();
+    this.peeking = false;
+    resize_to_cards(this, window, cx);
+}
+
+/// Resize the picker to fit the cards currently listed in the active layout.
+fn resize_to_cards(this: &mut AltTabApp, window: &mut Window, cx: &mut Context<AltTabApp>) {
+    let d = this.delegate.read(cx);
+    let monitor_size = window.display(cx).map(|display| {
+        let b = display.bounds();
+        (b.size.width.to_f64() as f32, b.size.height.to_f64() as f32)
+    });
+    let (w, h) = picker_dimensions(
+        d.windows.len().max(1),
+        this.max_columns,
+        monitor_size,
+        Chrome {
+            hotkey_hints: d.show_hotkey_hints,
+            action_bar: d.show_action_bar,
+            recent_strip: d.show_recent_strip,
+        },
+        this.layout_mode,
+        d.card_scale,
+    );
+    window.resize(size(px(w), px(h)));
+    cx.notify();
+}
+
+/// Fire-and-forget `command` through the platform shell.
+fn spawn_shell_command(command: &str) {
+    #[cfg(windows)]
+    let mut shell = {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    match shell.arg(command).spawn() {
+        // Reap the child so it doesn't linger as a zombie under the daemon.
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => eprintln!("[alt-tab] failed to run empty_enter_action command '{}': {}", command, e),
+    }
+}
+
+pub(crate) fn close_selected(this: &mut AltTabApp, cx: &mut Context<AltTabApp>) {
+    if let Some(win_id) = selected_window_id(this, cx) {
+        // The first close of a window with unsaved edits only arms; the app's
+        // own save prompt may not survive the picker taking focus back.
+        if this.close_armed != Some(win_id) && platform::window_has_unsaved_changes(win_id) {
+            this.close_armed = Some(win_id);
+            cx.notify();
+            return;
+        }
+        this.close_armed = None;
+        platform::close_window(win_id);
+        this.delegate.update(cx, |s, _cx| s.remove_window(win_id));
+        cx.notify();
+    }
+}
+
+pub(crate) fn quit_selected(this: &mut AltTabApp, cx: &mut Context<AltTabApp>) {
+    if let Some(win_id) = selected_window_id(this, cx) {
+        let app_name = this
+            .delegate
+            .read(cx)
+            .windows
+            .iter()
+            .find(|w| w.id == win_id)
+            .map(|w| w.app_name.clone());
+        platform::quit_app(win_id);
+        if let Some(app_name) = app_name {
+            this.delegate
+                .update(cx, |s, _cx| s.remove_app_windows(&app_name));
+        }
+        cx.notify();
+    }
+}
+
+/// Ctrl+N: ask the selected window's app for a new window, then get out of the way.
+fn new_window_for_selected(this: &mut AltTabApp, window: &mut Window, cx: &mut Context<AltTabApp>) {
+    let Some(win_id) = selected_window_id(this, cx) else {
+        return;
+    };
+    let Some(app_name) = this
+        .delegate
         .read(cx)
-        .selected_index
-        .and_then(|ix| this.delegate.read(cx).windows.get(ix).map(|w| w.id))
+        .windows
+        .iter()
+        .find(|w| w.id == win_id)
+        .map(|w| w.app_name.clone())
+    else {
+        return;
+    };
+    this.dismiss(window, cx);
+    if !platform::open_new_window(win_id, &app_name) {
+        eprintln!("[alt-tab] could not open a new window for {}", app_name);
+    }
+}
+
+pub(crate) fn minimize_selected(this: &mut AltTabApp, cx: &mut Context<AltTabApp>) {
+    if let Some(win_id) = selected_window_id(this, cx) {
+        platform::minimize_window_by_id(win_id);
+        this.delegate.update(cx, |s, _cx| s.mark_minimized(win_id));
+        cx.notify();
+    }
+}
+
+fn activate_index(this: &mut AltTabApp, ix: usize, window: &mut Window, cx: &mut Context<AltTabApp>) {
+    if ix < this.delegate.read(cx).windows.len() {
+        this.delegate.update(cx, |s, _cx| s.selected_index = Some(ix));
+        this.activate_selected(window, cx);
+    }
+}
+
+/// With vim_navigation, hjkl alias the arrows so they share the arrow branches
+/// (and their column math); Shift+L still toggles the layout.
+fn vim_alias(key: &str, vim: bool, shift: bool) -> &str {
+    match key {
+        "h" if vim => "left",
+        "j" if vim => "down",
+        "k" if vim => "up",
+        "l" if vim && !shift => "right",
+        key => key,
+    }
+}
+
+/// Digit or home-row hint keys. Returns whether the key was consumed.
+fn handle_quick_select(
+    this: &mut AltTabApp,
+    event: &gpui::KeyDownEvent,
+    window: &mut Window,
+    cx: &mut Context<AltTabApp>,
+) -> bool {
+    let key = event.keystroke.key.as_str();
+    match this.delegate.read(cx).quick_select {
+        QuickSelect::None => false,
+        QuickSelect::Numbers => {
+            let Some(ix) = shortcut_index(key) else {
+                return false;
+            };
+            activate_index(this, ix, window, cx);
+            true
+        }
+        QuickSelect::HomeRow => {
+            let modifiers = &event.keystroke.modifiers;
+            let mut chars = key.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                return false;
+            };
+            let keys = hint_keys(this.delegate.read(cx).vim_navigation);
+            if modifiers.shift || modifiers.control || modifiers.platform || !keys.contains(&ch) {
+                return false;
+            }
+            this.hint_prefix.push(ch);
+            let hints = home_row_hints(this.delegate.read(cx).windows.len(), keys);
+            match match_hint(&hints, &this.hint_prefix) {
+                HintMatch::Complete(ix) => {
+                    this.hint_prefix.clear();
+                    activate_index(this, ix, window, cx);
+                }
+                HintMatch::Partial => {}
+                HintMatch::None => this.hint_prefix.clear(),
+            }
+            cx.notify();
+            true
+        }
+    }
 }
 
 pub(crate) fn handle_key_down(
@@ -27,40 +254,37 @@ pub(crate) fn handle_key_down(
         event.keystroke.modifiers.control,
         event.keystroke.modifiers.platform,
     );
-    match event.keystroke.key.as_str() {
-        "escape" | "esc" => {
-            PICKER_VISIBLE.store(false, Ordering::Relaxed);
-            platform::dismiss_picker(window);
-        }
-        "w" => {
-            if let Some(win_id) = selected_window_id(this, cx) {
-                platform::close_window(win_id);
-                this.delegate.update(cx, |s, _cx| s.remove_window(win_id));
-                cx.notify();
-            }
+    this.restart_idle_timer(window, cx);
+    if handle_quick_select(this, event, window, cx) {
+        return;
+    }
+    let vim = this.delegate.read(cx).vim_navigation;
+    let key = vim_alias(event.keystroke.key.as_str(), vim, event.keystroke.modifiers.shift);
+    match key {
+        "escape" | "esc" if !this.hint_prefix.is_empty() => {
+            this.hint_prefix.clear();
+            cx.notify();
         }
-        "q" => {
-            if let Some(win_id) = selected_window_id(this, cx) {
-                let app_name = this
-                    .delegate
-                    .read(cx)
-                    .windows
-                    .iter()
-                    .find(|w| w.id == win_id)
-                    .map(|w| w.app_name.clone());
-                platform::quit_app(win_id);
-                if let Some(app_name) = app_name {
-                    this.delegate
-                        .update(cx, |s, _cx| s.remove_app_windows(&app_name));
-                }
-                cx.notify();
-            }
+        "escape" | "esc" if this.peeking => {
+            this.peeking = false;
+            cx.notify();
         }
-        "r" => {
-            if let Some(win_id) = selected_window_id(this, cx) {
-                platform::minimize_window_by_id(win_id);
-                this.delegate.update(cx, |s, _cx| s.mark_minimized(win_id));
-                cx.notify();
+        "escape" | "esc" => this.dismiss(window, cx),
+        "space" => {
+            this.peeking = !this.peeking;
+            cx.notify();
+        }
+        "n" if event.keystroke.modifiers.control || event.keystroke.modifiers.platform => {
+            new_window_for_selected(this, window, cx)
+        }
+        "w" => close_selected(this, cx),
+        "q" => quit_selected(this, cx),
+        "r" => minimize_selected(this, cx),
+        "m" => toggle_minimized(this, cx),
+        "l" => toggle_layout(this, window, cx),
+        "e" => {
+            if this.delegate.update(cx, |s, _cx| s.toggle_expand_app()) {
+                resize_to_cards(this, window, cx);
             }
         }
         "enter" => {
@@ -70,9 +294,16 @@ pub(crate) fn handle_key_down(
                 .selected_index
                 .and_then(|ix| this.delegate.read(cx).windows.get(ix).map(|w| w.id));
             if win_id.is_some() {
-                this.delegate.update(cx, |s, _cx| {
-                    s.activate_selected(window);
-                });
+                this.activate_selected(window, cx);
+            } else if this.delegate.read(cx).windows.is_empty() {
+                match this.empty_enter_action.clone() {
+                    EmptyEnterAction::None => {}
+                    EmptyEnterAction::Close => this.dismiss(window, cx),
+                    EmptyEnterAction::RunCommand(command) => {
+                        spawn_shell_command(&command);
+                        this.dismiss(window, cx);
+                    }
+                }
             }
         }
         "tab" => {
@@ -92,32 +323,28 @@ pub(crate) fn handle_key_down(
             cx.notify();
         }
         "right" | "arrowright" => {
-            let total = this.delegate.read(cx).windows.len();
-            let cols = rendered_column_count(window, total);
+            let cols = nav_columns(this, window, cx);
             this.delegate.update(cx, |s, _cx| {
                 s.select_right(cols);
             });
             cx.notify();
         }
         "left" | "arrowleft" => {
-            let total = this.delegate.read(cx).windows.len();
-            let cols = rendered_column_count(window, total);
+            let cols = nav_columns(this, window, cx);
             this.delegate.update(cx, |s, _cx| {
                 s.select_left(cols);
             });
             cx.notify();
         }
         "down" | "arrowdown" => {
-            let total = this.delegate.read(cx).windows.len();
-            let cols = rendered_column_count(window, total);
+            let cols = nav_columns(this, window, cx);
             this.delegate.update(cx, |s, _cx| {
                 s.select_down(cols);
             });
             cx.notify();
         }
         "up" | "arrowup" => {
-            let total = this.delegate.read(cx).windows.len();
-            let cols = rendered_column_count(window, total);
+            let cols = nav_columns(this, window, cx);
             this.delegate.update(cx, |s, _cx| {
                 s.select_up(cols);
             });
@@ -126,3 +353,29 @@ pub(crate) fn handle_key_down(
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::vim_alias;
+
+    #[test]
+    fn hjkl_map_to_arrow_keys_when_enabled() {
+        assert_eq!(vim_alias("h", true, false), "left");
+        assert_eq!(vim_alias("j", true, false), "down");
+        assert_eq!(vim_alias("k", true, false), "up");
+        assert_eq!(vim_alias("l", true, false), "right");
+    }
+
+    #[test]
+    fn letters_pass_through_when_disabled() {
+        for key in ["h", "j", "k", "l"] {
+            assert_eq!(vim_alias(key, false, false), key);
+        }
+    }
+
+    #[test]
+    fn shift_l_keeps_layout_toggle() {
+        assert_eq!(vim_alias("l", true, true), "l");
+        assert_eq!(vim_alias("left", true, false), "left");
+    }
+}
@@ -1,14 +1,116 @@
 use super::PICKER_VISIBLE;
+use crate::config::PreviewQuality;
 use crate::delegate::WindowDelegate;
-use crate::layout::{PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH};
+use crate::layout::preview_max_size;
 use crate::platform;
-use crate::preview::{bgra_to_render_image, fast_pixel_hash};
+use crate::preview::{self, bgra_to_render_image, fast_pixel_hash};
 use gpui::{AsyncApp, Entity, Task, WeakEntity};
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const LIVE_PREVIEW_INTERVAL_MS: u64 = 500;
+/// Windows over `large_window_capture_pixels` are captured every Nth tick.
+const LARGE_WINDOW_TICK_DIVISOR: u64 = 4;
+/// Upper bound for `stream_selected_fps`.
+const MAX_STREAM_FPS: u32 = 30;
+/// Consecutive changed captures before a preview counts as animated.
+const ANIMATED_STREAK: u32 = 3;
+/// Consecutive identical captures before a window backs off to `STABLE_INTERVAL`.
+const STABLE_CYCLES: u32 = 3;
+/// Capture cadence for windows whose pixels have stopped changing.
+const STABLE_INTERVAL: Duration = Duration::from_millis(2000);
+
+/// Last content hash per window. Windows whose pixels stay identical for
+/// `STABLE_CYCLES` captures are only due every `STABLE_INTERVAL` until they
+/// change again.
+#[derive(Default)]
+struct ChangeTracker {
+    windows: HashMap<u32, WindowCapture>,
+}
+
+struct WindowCapture {
+    hash: u64,
+    stable_count: u32,
+    next_due: Option<Instant>,
+}
+
+impl ChangeTracker {
+    fn is_due(&self, wid: u32, now: Instant) -> bool {
+        self.windows
+            .get(&wid)
+            .and_then(|c| c.next_due)
+            .map_or(true, |due| now >= due)
+    }
+
+    /// Record a capture of `wid`; true if its pixels changed (or are new).
+    fn record(&mut self, wid: u32, hash: u64, now: Instant) -> bool {
+        let Some(capture) = self.windows.get_mut(&wid) else {
+            self.windows.insert(wid, WindowCapture { hash, stable_count: 0, next_due: None });
+            return true;
+        };
+        if capture.hash != hash {
+            *capture = WindowCapture { hash, stable_count: 0, next_due: None };
+            return true;
+        }
+        capture.stable_count = capture.stable_count.saturating_add(1);
+        if capture.stable_count >= STABLE_CYCLES {
+            capture.next_due = Some(now + STABLE_INTERVAL);
+        }
+        false
+    }
+
+    fn retain(&mut self, keep: impl Fn(u32) -> bool) {
+        self.windows.retain(|id, _| keep(*id));
+    }
+
+    fn clear(&mut self) {
+        self.windows.clear();
+    }
+}
+
+/// Tracks previews whose pixels change on every capture (video, animation)
+/// so they can be refreshed at a capped rate instead of every round.
+#[derive(Default)]
+struct AnimatedThrottle {
+    streaks: HashMap<u32, u32>,
+    last_capture: HashMap<u32, Instant>,
+}
+
+impl AnimatedThrottle {
+    fn is_throttled(&self, wid: u32, interval: Duration) -> bool {
+        self.streaks.get(&wid).copied().unwrap_or(0) >= ANIMATED_STREAK
+            && self
+                .last_capture
+                .get(&wid)
+                .is_some_and(|t| t.elapsed() < interval)
+    }
+
+    fn record(&mut self, wid: u32, changed: bool) {
+        self.last_capture.insert(wid, Instant::now());
+        let streak = self.streaks.entry(wid).or_insert(0);
+        *streak = if changed { streak.saturating_add(1) } else { 0 };
+    }
+
+    fn clear(&mut self) {
+        self.streaks.clear();
+        self.last_capture.clear();
+    }
+}
+
+/// What one pass of the loop captures.
+struct CaptureRound {
+    ids: Vec<(usize, u32)>,
+    quality: PreviewQuality,
+    capture_cursor: bool,
+    screen_capture_kit: bool,
+    card_scale: f32,
+    show_badges: bool,
+    /// On-screen cards with no preview that may have one spilled to disk.
+    reload: Vec<u32>,
+    /// Fast interval for the selected window when streaming is on.
+    stream_interval_ms: Option<u64>,
+}
 
 pub(crate) fn spawn(
     delegate: Entity<WindowDelegate>,
@@ -19,70 +121,194 @@ pub(crate) fn spawn(
             let cx = cx.clone();
             async move {
                 let executor = cx.background_executor().clone();
-                let mut prev_hashes: HashMap<u32, u64> = HashMap::new();
+                let mut changes = ChangeTracker::default();
+                let mut animated = AnimatedThrottle::default();
                 let mut first_visible = true;
+                let mut tick: u64 = 0;
+                // Between full rounds only the selected window is captured at the stream rate.
+                let mut interval_ms = LIVE_PREVIEW_INTERVAL_MS;
+                let mut since_full_ms = LIVE_PREVIEW_INTERVAL_MS;
                 loop {
                     if first_visible && PICKER_VISIBLE.load(Ordering::Relaxed) {
                         first_visible = false;
+                        since_full_ms = LIVE_PREVIEW_INTERVAL_MS;
                     } else {
-                        executor
-                            .timer(Duration::from_millis(LIVE_PREVIEW_INTERVAL_MS))
-                            .await;
+                        executor.timer(Duration::from_millis(interval_ms)).await;
+                        since_full_ms += interval_ms;
                     }
                     if !PICKER_VISIBLE.load(Ordering::Relaxed) {
-                        prev_hashes.clear();
+                        changes.clear();
+                        animated.clear();
                         first_visible = true;
+                        tick = 0;
+                        interval_ms = LIVE_PREVIEW_INTERVAL_MS;
                         continue;
                     }
-                    let window_ids: Vec<(usize, u32)> = cx
-                        .update(|app_cx| {
-                            delegate
-                                .read(app_cx)
-                                .windows
-                                .iter()
-                                .enumerate()
-                                .filter(|(_, w)| !w.is_minimized)
-                                .map(|(i, w)| (i, w.id))
+                    let full_round = since_full_ms >= LIVE_PREVIEW_INTERVAL_MS;
+                    let large_window_turn = full_round && tick % LARGE_WINDOW_TICK_DIVISOR == 0;
+                    if full_round {
+                        since_full_ms = 0;
+                        tick += 1;
+                    }
+                    let Ok(round) = cx.update(|app_cx| {
+                        let state = delegate.read(app_cx);
+                        // A preview dropped for the memory budget must be re-sent even if unchanged.
+                        changes.retain(|id| state.live_previews.contains_key(&id));
+                        let now = Instant::now();
+                        let limit = state.large_window_capture_pixels;
+                        let selected = state.selected_window_id();
+                        let animated_interval =
+                            Duration::from_millis(state.animated_preview_interval_ms);
+                        let ids = state
+                            .windows
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, w)| !w.is_minimized)
+                            // Never capture the picker itself, even if a WM lists it briefly.
+                            .filter(|(_, w)| w.title != platform::PICKER_WINDOW_TITLE)
+                            .filter(|(_, w)| state.wants_preview(w))
+                            // Under a memory budget only cards in view are kept fresh.
+                            .filter(|(_, w)| state.preview_budget.is_none() || state.card_on_screen(w.id))
+                            .filter(|(_, w)| full_round || selected == Some(w.id))
+                            // Unchanging windows are captured less often; the selected one never backs off.
+                            .filter(|(_, w)| selected == Some(w.id) || changes.is_due(w.id, now))
+                            .filter(|(_, w)| {
+                                selected == Some(w.id)
+                                    || !animated.is_throttled(w.id, animated_interval)
+                            })
+                            .filter(|(_, w)| {
+                                large_window_turn
+                                    || limit == 0
+                                    || ((w.width * w.height) as u64) <= limit
+                            })
+                            .map(|(i, w)| (i, w.id))
+                            .collect::<Vec<_>>();
+                        let reload = if state.preview_budget.is_some() {
+                            ids.iter()
+                                .map(|&(_, id)| id)
+                                .filter(|id| !state.live_previews.contains_key(id))
                                 .collect()
-                        })
-                        .unwrap_or_default();
+                        } else {
+                            Vec::new()
+                        };
+                        let stream_fps = state.stream_selected_fps.min(MAX_STREAM_FPS);
+                        CaptureRound {
+                            ids,
+                            quality: state.preview_quality,
+                            capture_cursor: state.capture_cursor,
+                            screen_capture_kit: state.screen_capture_kit,
+                            card_scale: state.card_scale,
+                            show_badges: state.show_app_badges,
+                            reload,
+                            stream_interval_ms: (stream_fps > 0).then(|| 1000 / stream_fps as u64),
+                        }
+                    }) else {
+                        continue;
+                    };
+                    interval_ms = round
+                        .stream_interval_ms
+                        .unwrap_or(LIVE_PREVIEW_INTERVAL_MS)
+                        .min(LIVE_PREVIEW_INTERVAL_MS);
+                    let CaptureRound {
+                        ids: window_ids,
+                        quality,
+                        capture_cursor,
+                        screen_capture_kit,
+                        card_scale,
+                        show_badges,
+                        reload,
+                        ..
+                    } = round;
+                    if !reload.is_empty() {
+                        let restored = executor
+                            .spawn(async move {
+                                reload
+                                    .into_iter()
+                                    .filter_map(|id| Some((id, preview::load_spilled(id)?)))
+                                    .collect::<Vec<_>>()
+                            })
+                            .await;
+                        if !restored.is_empty() {
+                            let _ = cx.update(|app_cx| {
+                                let _ = delegate.update(app_cx, |state, cx| {
+                                    for (id, image) in restored {
+                                        state.live_previews.entry(id).or_insert(image);
+                                    }
+                                    cx.notify();
+                                });
+                            });
+                        }
+                    }
+                    if show_badges && full_round {
+                        let badges = executor.spawn(async { platform::get_app_badges() }).await;
+                        let _ = cx.update(|app_cx| {
+                            let _ = delegate.update(app_cx, |state, cx| {
+                                if state.app_badges != badges {
+                                    state.app_badges = badges;
+                                    cx.notify();
+                                }
+                            });
+                        });
+                    }
                     if window_ids.is_empty() {
                         continue;
                     }
                     let id_map: Vec<(usize, u32)> = window_ids.clone();
                     let captured = executor
                         .spawn(async move {
+                            let (max_w, max_h) = preview_max_size(card_scale);
                             platform::capture_previews_cg(
                                 &window_ids,
-                                PREVIEW_MAX_WIDTH,
-                                PREVIEW_MAX_HEIGHT,
+                                max_w,
+                                max_h,
+                                quality,
+                                capture_cursor,
+                                screen_capture_kit,
                             )
                         })
                         .await;
-                    let mut changed = false;
                     let list = delegate.clone();
+                    let mut failed = Vec::new();
+                    let mut succeeded = Vec::new();
+                    let mut updated = Vec::new();
                     for (idx, rgba_opt) in captured {
-                        let Some(rgba) = rgba_opt else { continue };
                         let Some(&(_, wid)) = id_map.iter().find(|(i, _)| *i == idx) else {
                             continue;
                         };
+                        let Some(rgba) = rgba_opt else {
+                            failed.push(wid);
+                            continue;
+                        };
+                        succeeded.push(wid);
                         let hash = fast_pixel_hash(&rgba.data);
-                        if prev_hashes.get(&wid) == Some(&hash) {
+                        let changed = changes.record(wid, hash, Instant::now());
+                        animated.record(wid, changed);
+                        if !changed {
                             continue;
                         }
-                        prev_hashes.insert(wid, hash);
                         if let Some(render_img) =
                             bgra_to_render_image(&rgba.data, rgba.width, rgba.height)
                         {
-                            let _ = cx.update(|app_cx| {
-                                let _ = list.update(app_cx, |state, cx| {
-                                    state.live_previews.insert(wid, render_img);
-                                    cx.notify();
-                                });
-                            });
-                            changed = true;
+                            updated.push((wid, render_img));
                         }
                     }
+                    // One update and one notify per round, however many previews changed.
+                    let mut changed = !updated.is_empty();
+                    let _ = cx.update(|app_cx| {
+                        let _ = list.update(app_cx, |state, cx| {
+                            state.live_previews.extend(updated);
+                            state.enforce_preview_budget();
+                            if state.record_capture_results(&failed, &succeeded) {
+                                changed = true;
+                            }
+                            if state.stale_previews_changed() {
+                                changed = true;
+                            }
+                            if changed {
+                                cx.notify();
+                            }
+                        });
+                    });
                     if changed {
                         let _ = cx.update(|app_cx| {
                             let _ = this.update(
@@ -98,3 +324,36 @@ pub(crate) fn spawn(
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_window_backs_off_until_it_changes() {
+        let mut changes = ChangeTracker::default();
+        let t0 = Instant::now();
+        assert!(changes.record(1, 7, t0));
+        for _ in 0..STABLE_CYCLES {
+            assert!(changes.is_due(1, t0));
+            assert!(!changes.record(1, 7, t0));
+        }
+        assert!(!changes.is_due(1, t0));
+        assert!(changes.is_due(1, t0 + STABLE_INTERVAL));
+        assert!(changes.record(1, 8, t0));
+        assert!(changes.is_due(1, t0));
+    }
+
+    #[test]
+    fn unknown_or_forgotten_windows_are_due() {
+        let mut changes = ChangeTracker::default();
+        let t0 = Instant::now();
+        assert!(changes.is_due(2, t0));
+        for _ in 0..=STABLE_CYCLES {
+            changes.record(2, 1, t0);
+        }
+        changes.retain(|id| id != 2);
+        assert!(changes.is_due(2, t0));
+        assert!(changes.record(2, 1, t0));
+    }
+}
@@ -1,16 +1,19 @@
 pub(crate) mod alt_poll;
+pub(crate) mod fade;
+mod idle;
 mod input;
 mod live_preview;
 mod render;
 
-use crate::config::{ActionMode, LabelConfig};
+use crate::config::{ActionMode, AltTabConfig, EmptyEnterAction, LayoutMode, Modifier};
 use crate::delegate::WindowDelegate;
-use crate::platform;
-use crate::platform::WindowInfo;
+use crate::platform::{self, WindowInfo};
+use crate::preview::TimedPreviews;
 use gpui::*;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub(crate) static PICKER_VISIBLE: AtomicBool = AtomicBool::new(false);
 
@@ -18,29 +21,55 @@ pub(crate) struct AltTabApp {
     pub(crate) delegate: Entity<WindowDelegate>,
     pub(crate) focus_handle: FocusHandle,
     pub(crate) action_mode: ActionMode,
+    /// Key whose release ends a hold-to-switch.
+    pub(crate) action_modifier: Modifier,
     pub(crate) alt_was_held: bool,
     pub(crate) _alt_poll_task: Option<Task<()>>,
     _live_preview_task: Option<Task<()>>,
+    pub(crate) close_fade_ms: u64,
+    pub(crate) auto_dismiss_ms: Option<u64>,
+    pub(crate) activation_debounce_ms: u64,
+    pub(crate) min_visible_ms: u64,
+    pub(crate) empty_enter_action: EmptyEnterAction,
+    pub(crate) pinned_on_top: bool,
+    /// Space toggles a full-size view of the selected window's preview. It is
+    /// drawn inside the picker, so no window is raised and cancel has no
+    /// stacking order to restore.
+    pub(crate) peeking: bool,
+    /// Home-row hint keys typed so far.
+    pub(crate) hint_prefix: String,
+    /// Active layout; starts from `display.layout` and flips with `L`.
+    pub(crate) layout_mode: LayoutMode,
+    pub(crate) max_columns: usize,
+    /// A modal dialog was frontmost when the picker opened; switching may be refused.
+    pub(crate) modal_warning: bool,
+    /// Window whose close was held back for unsaved changes; `W` again closes it.
+    pub(crate) close_armed: Option<u32>,
+    pub(crate) closing_since: Option<Instant>,
+    /// Scroll state of the card grid, so the selection can be kept in view.
+    pub(crate) grid_scroll: ScrollHandle,
+    /// Selection the grid was last scrolled to.
+    pub(crate) scrolled_selection: Option<usize>,
+    /// Card under the mouse, enlarged by `hover_scale`.
+    pub(crate) hovered_card: Option<usize>,
+    _fade_task: Option<Task<()>>,
+    /// Pending `auto_dismiss_ms` countdown; dropping it cancels it.
+    _idle_task: Option<Task<()>>,
 }
 
 impl AltTabApp {
     pub(crate) fn new(
         window: &mut Window,
         cx: &mut Context<Self>,
-        action_mode: ActionMode,
+        config: &AltTabConfig,
         initial_windows: Vec<WindowInfo>,
-        label_config: LabelConfig,
-        transparent_background: bool,
-        card_bg_color: u32,
-        card_bg_opacity: f32,
-        show_debug_overlay: bool,
-        show_hotkey_hints: bool,
-        cycle_on_open: bool,
-        initial_previews: HashMap<u32, Arc<RenderImage>>,
+        initial_previews: TimedPreviews,
         icon_cache: HashMap<String, Arc<RenderImage>>,
     ) -> Self {
+        let action_mode = config.action_mode.clone();
+        let cycle_on_open = config.cycles_on_open();
         let win_delegate =
-            WindowDelegate::new_with_previews(initial_windows.clone(), label_config, transparent_background, card_bg_color, card_bg_opacity, show_debug_overlay, show_hotkey_hints, initial_previews, icon_cache);
+            WindowDelegate::new_with_previews(initial_windows.clone(), config, initial_previews, icon_cache);
         let delegate = cx.new(|_cx| win_delegate);
 
         if cycle_on_open && initial_windows.len() >= 2 {
@@ -56,10 +85,9 @@ impl AltTabApp {
         let _focus_out_subscription = cx.on_focus_out(
             &focus_handle_for_sub,
             window,
-            |this, _event, window, _cx| {
-                if this.action_mode != ActionMode::HoldToSwitch {
-                    PICKER_VISIBLE.store(false, Ordering::Relaxed);
-                    platform::dismiss_picker(window);
+            |this, _event, window, cx| {
+                if this.action_mode != ActionMode::HoldToSwitch && !this.pinned_on_top {
+                    this.dismiss(window, cx);
                 }
             },
         );
@@ -76,14 +104,35 @@ impl AltTabApp {
             delegate,
             focus_handle,
             action_mode: action_mode.clone(),
+            action_modifier: config.action_modifier,
             alt_was_held: true,
             _alt_poll_task: None,
             _live_preview_task: Some(live_preview_task),
+            close_fade_ms: fade::configured_ms(config),
+            auto_dismiss_ms: config.display.auto_dismiss_ms,
+            activation_debounce_ms: config.activation_debounce_ms,
+            min_visible_ms: config.min_visible_ms,
+            empty_enter_action: config.empty_enter_action.clone(),
+            pinned_on_top: config.pin_on_top(),
+            peeking: false,
+            hint_prefix: String::new(),
+            layout_mode: config.display.layout,
+            max_columns: config.display.max_columns,
+            modal_warning: false,
+            close_armed: None,
+            closing_since: None,
+            grid_scroll: ScrollHandle::new(),
+            scrolled_selection: None,
+            hovered_card: None,
+            _fade_task: None,
+            _idle_task: None,
         };
 
         if action_mode == ActionMode::HoldToSwitch {
             alt_poll::start(&mut app, gpui_window_handle, cx);
         }
+        app.restart_idle_timer(window, cx);
+        app.check_unsaved_changes(cx);
 
         app
     }
@@ -92,23 +141,71 @@ impl AltTabApp {
         &mut self,
         windows: Vec<WindowInfo>,
         reset_selection: bool,
-        previews: HashMap<u32, Arc<RenderImage>>,
+        previews: TimedPreviews,
         icons: HashMap<String, Arc<RenderImage>>,
         cx: &mut Context<Self>,
     ) {
         self.delegate.update(cx, |state, cx| {
             state.set_windows(windows, reset_selection);
             if !previews.is_empty() {
-                state.live_previews = previews;
-            }
-            if !icons.is_empty() {
-                state.icon_cache = icons;
+                state.live_previews.clear();
+                state.insert_previews(previews);
             }
+            state.merge_icons(icons);
             cx.notify();
         });
+        self.check_unsaved_changes(cx);
         cx.notify();
     }
 
+    /// Badge listed windows with unsaved changes. One AX query per window on
+    /// macOS, so it runs in the background and only for the cards shown.
+    fn check_unsaved_changes(&mut self, cx: &mut Context<Self>) {
+        let ids: Vec<u32> = self.delegate.read(cx).windows.iter().map(|w| w.id).collect();
+        cx.spawn(async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+            let unsaved: HashSet<u32> = cx
+                .background_executor()
+                .spawn(async move {
+                    ids.into_iter().filter(|id| platform::window_has_unsaved_changes(*id)).collect()
+                })
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                app.delegate.update(cx, |s, cx| {
+                    s.mark_unsaved(&unsaved);
+                    cx.notify();
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Hide the picker without activating anything, fading out if configured.
+    /// Activation paths hide instantly so the raised window is never behind a fade.
+    pub(crate) fn dismiss(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        fade::start(self, window, cx);
+    }
+
+    /// Switch to the selected window. A refused switch (e.g. a modal is up)
+    /// leaves the picker open with the modal warning shown.
+    pub(crate) fn activate_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.delegate.update(cx, |s, _cx| s.activate_selected(window)) {
+            self.modal_warning = true;
+            cx.notify();
+        }
+    }
+
+    /// Abort an in-flight fade, e.g. when the picker is reopened mid-fade.
+    pub(crate) fn cancel_dismiss(&mut self) {
+        self._fade_task = None;
+        self.closing_since = None;
+    }
+
+    /// Restart the Sticky-mode idle countdown after user activity.
+    pub(crate) fn restart_idle_timer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        idle::restart(self, window, cx);
+    }
+
     pub(crate) fn start_alt_poll(
         &mut self,
         window_handle: AnyWindowHandle,
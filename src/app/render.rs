@@ -1,24 +1,109 @@
 use super::AltTabApp;
+use crate::config::{ActionMode, LabelFields, LayoutMode, Modifier, QuickSelect};
+use crate::delegate::shortcuts::shortcut_labels;
 use crate::layout::{GRID_CARD_HEIGHT, GRID_CARD_WIDTH, GRID_PREVIEW_HEIGHT, GRID_PREVIEW_WIDTH};
+use crate::monitor::monitor_for_bounds;
+use crate::platform::WindowInfo;
 use crate::window_source::preview_tile;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// One full glow pulse (dim → bright → dim).
+const GLOW_PULSE_MS: u64 = 1600;
+/// Windows shown in the recent strip.
+const RECENT_STRIP_LEN: usize = 5;
+
+impl AltTabApp {
+    /// Ids of the cards inside the grid's viewport as of the last layout; every
+    /// card counts until the grid has been laid out once.
+    fn cards_on_screen(&self, cx: &App) -> Vec<u32> {
+        let viewport = self.grid_scroll.bounds();
+        let offset = self.grid_scroll.offset();
+        self.delegate
+            .read(cx)
+            .windows
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| match self.grid_scroll.bounds_for_item(*i) {
+                Some(card) => {
+                    card.top() + offset.y < viewport.bottom() && card.bottom() + offset.y > viewport.top()
+                }
+                None => true,
+            })
+            .map(|(_, w)| w.id)
+            .collect()
+    }
+}
 
 impl Render for AltTabApp {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let delegate = self.delegate.clone();
+        let on_screen = self.cards_on_screen(cx);
+        delegate.update(cx, |d, _| d.note_previews_shown(on_screen));
         let d_ref = delegate.read(cx);
         let transparent_bg = d_ref.transparent_background;
         let show_debug_overlay = d_ref.show_debug_overlay;
         let show_hotkey_hints = d_ref.show_hotkey_hints;
+        let show_action_bar = d_ref.show_action_bar && d_ref.selected_window_id().is_some();
+        // By focus history, whatever the grid's order; the current window is skipped.
+        let recent: Vec<(usize, String, Option<std::sync::Arc<RenderImage>>)> = if d_ref.show_recent_strip {
+            let stamps = crate::focus_history::snapshot();
+            let mut by_recency: Vec<(usize, &WindowInfo)> = d_ref
+                .windows
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| !w.is_minimized && Some(w.id) != d_ref.current_window_id)
+                .collect();
+            // Never-focused windows go last, in list order.
+            by_recency.sort_by_key(|(_, w)| std::cmp::Reverse(stamps.get(&w.id).copied()));
+            by_recency
+                .into_iter()
+                .take(RECENT_STRIP_LEN)
+                .map(|(i, w)| (i, w.app_name.clone(), d_ref.icon_cache.get(&w.app_name).cloned()))
+                .collect()
+        } else {
+            Vec::new()
+        };
         let card_bg_rgba = {
             let alpha = (d_ref.card_bg_opacity.clamp(0.0, 1.0) * 255.0) as u32;
             (d_ref.card_bg_color << 8) | alpha
         };
+        let peek_image = self
+            .peeking
+            .then(|| d_ref.selected_window_id())
+            .flatten()
+            .and_then(|id| d_ref.live_previews.get(&id).cloned());
+        let selected_index = d_ref.selected_index;
         drop(d_ref);
+        // Only on change, so a wheel scroll isn't undone by the next render.
+        if selected_index != self.scrolled_selection {
+            if let Some(ix) = selected_index {
+                self.grid_scroll.scroll_to_item(ix);
+            }
+            self.scrolled_selection = selected_index;
+        }
+        let display_bounds: Vec<Bounds<Pixels>> = cx.displays().iter().map(|d| d.bounds()).collect();
+        let fade_opacity = super::fade::opacity(self);
+        let hints = hotkey_hints(&self.action_mode, self.action_modifier, delegate.read(cx).vim_navigation);
+        let list_layout = self.layout_mode == LayoutMode::List;
+        let ui_font = delegate.read(cx).font.clone();
+        let label_size = px(12.0 * delegate.read(cx).font_scale);
+        let accent = delegate.read(cx).accent_color;
+        let selection_glow = delegate.read(cx).selection_glow;
+        let glow_pulse = delegate.read(cx).selection_glow_pulse;
+        let rtl = delegate.read(cx).rtl;
+        let hover_scale = delegate.read(cx).hover_scale;
+        let hovered_card = self.hovered_card.filter(|_| hover_scale > 1.0);
+        let card_scale = delegate.read(cx).card_scale;
+        let (card_w, card_h) = (GRID_CARD_WIDTH * card_scale, GRID_CARD_HEIGHT * card_scale);
+        let (preview_w, preview_h) = (GRID_PREVIEW_WIDTH * card_scale, GRID_PREVIEW_HEIGHT * card_scale);
 
         div()
             .track_focus(&self.focus_handle)
+            .when_some(fade_opacity, |s, opacity| s.opacity(opacity))
+            .when_some(ui_font, |s, font| s.font(font))
             .flex()
             .flex_col()
             .when(!transparent_bg, |s| s.bg(rgb(0x0f111a)))
@@ -27,6 +112,32 @@ impl Render for AltTabApp {
             .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
                 super::input::handle_key_down(this, event, window, cx);
             }))
+            .on_mouse_move(cx.listener(|this, _: &MouseMoveEvent, window, cx| {
+                this.restart_idle_timer(window, cx);
+            }))
+            .when(self.modal_warning, |s| {
+                s.child(
+                    // ── Modal warning ─────────────────────────────────────────────
+                    div()
+                        .px_4()
+                        .py_1()
+                        .bg(rgb(0x3a2a12))
+                        .text_color(rgb(0xe0b060))
+                        .text_xs()
+                        .child("A dialog is open in the current app — switching away may be blocked until it is closed."),
+                )
+            })
+            .when(self.close_armed.is_some(), |s| {
+                s.child(
+                    div()
+                        .px_4()
+                        .py_1()
+                        .bg(rgb(0x3a2a12))
+                        .text_color(rgb(0xe0b060))
+                        .text_xs()
+                        .child("This window has unsaved changes — press W again to close it anyway."),
+                )
+            })
             .when(show_hotkey_hints, |s| {
                 s.child(
                     // ── Hotkey hints bar ──────────────────────────────────────────
@@ -49,7 +160,7 @@ impl Render for AltTabApp {
                             div()
                                 .text_color(rgb(0x3a4252))
                                 .text_xs()
-                                .child("W close  ·  Q quit  ·  R minimize  ·  ↑↓←→ navigate  ·  ⏎ switch  ·  esc close"),
+                                .child(hints),
                         ),
                 )
             })
@@ -79,29 +190,87 @@ impl Render for AltTabApp {
                         ),
                 )
             })
+            .when(!recent.is_empty(), |s| {
+                let entity = cx.weak_entity();
+                s.child(
+                    // ── Recent windows strip ──────────────────────────────────────
+                    div()
+                        .px_4()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(rgb(0x1e2333))
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .children(recent.into_iter().map(|(i, app_name, icon)| {
+                            div()
+                                .id(("recent-window", i))
+                                .flex()
+                                .items_center()
+                                .gap_1()
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .max_w(px(160.0))
+                                .bg(rgb(0x1a1e2a))
+                                .hover(|s| s.bg(rgb(0x1e2640)))
+                                .cursor_pointer()
+                                .text_xs()
+                                .text_color(rgb(0x9fb1cf))
+                                .on_click(activate_on_click(entity.clone(), i))
+                                .when_some(icon, |el, icon| {
+                                    el.child(img(icon).w(px(14.0)).h(px(14.0)).rounded_sm().flex_shrink_0())
+                                })
+                                .child(div().text_ellipsis().overflow_hidden().child(app_name))
+                        })),
+                )
+            })
             .child(
                 // ── Content ───────────────────────────────────────────────────
-                div().flex_1().w_full().min_h_0().child({
+                div().flex_1().w_full().min_h_0().relative().child({
+                    // Borrowed, not cloned: this runs on every notify, including each
+                    // cycling keystroke, and the maps grow with the window count.
                     let d = delegate.read(cx);
-                    let windows = d.windows.clone();
+                    let windows = &d.windows;
+                    let wants_preview: Vec<bool> = windows.iter().map(|w| d.wants_preview(w)).collect();
                     let selected_index = d.selected_index;
-                    let label_config = d.label_config.clone();
-                    let live_previews = d.live_previews.clone();
-                    let icon_cache = d.icon_cache.clone();
+                    let label_config = &d.label_config;
+                    let live_previews = &d.live_previews;
+                    let icon_cache = &d.icon_cache;
+                    let icon_placeholder = d.icon_placeholder;
+                    let placeholder_image = d.placeholder_image.as_ref();
+                    // Home-row hints are unusable unseen, so they are always drawn.
+                    let shortcuts = if d.show_shortcut_badges || d.quick_select == QuickSelect::HomeRow {
+                        shortcut_labels(d.quick_select, windows.len(), d.vim_navigation)
+                    } else {
+                        Vec::new()
+                    };
+                    let hint_prefix = self.hint_prefix.as_str();
+                    let app_badges = &d.app_badges;
+                    let app_counts =
+                        (d.collapse_apps && d.expanded_app.is_none()).then_some(&d.app_window_counts);
+                    // Collapsed cards already carry a "×N" badge.
+                    let mut sibling_counts: HashMap<&str, usize> = HashMap::new();
+                    if d.show_app_window_count && app_counts.is_none() {
+                        for w in windows.iter() {
+                            *sibling_counts.entry(w.app_name.as_str()).or_insert(0) += 1;
+                        }
+                    }
 
                     let entity = cx.weak_entity();
                     div()
                         .id("preview-grid")
                         .flex()
-                        .flex_row()
-                        .flex_wrap()
-                        .content_start()
+                        .when(!list_layout && !rtl, |s| s.flex_row())
+                        .when(!list_layout && rtl, |s| s.flex_row_reverse())
+                        .when(!list_layout, |s| s.flex_wrap().content_start().gap_3())
+                        .when(list_layout, |s| s.flex_col().gap_1())
                         .w_full()
                         .h_full()
                         .overflow_y_scroll()
+                        .track_scroll(&self.grid_scroll)
                         .px_5()
                         .py_4()
-                        .gap_3()
                         .when(windows.is_empty(), |s| {
                             s.items_center().justify_center().child(
                                 div()
@@ -110,80 +279,154 @@ impl Render for AltTabApp {
                                     .child("Scanning windows..."),
                             )
                         })
-                        .children(windows.into_iter().enumerate().map(|(i, win)| {
+                        .children(windows.iter().enumerate().map(|(i, win)| {
                             let is_selected = selected_index == Some(i);
-                            let entity_for_click = entity.clone();
-                            div()
+                            // Faint accent ring on the window that was in front, unless selected.
+                            let current_ring = (!is_selected && d.current_window_id == Some(win.id))
+                                .then_some(rgba((accent << 8) | 0x80));
+                            if list_layout {
+                                let label = label_config.format(&LabelFields {
+                                    app_name: &win.app_name,
+                                    title: &win.title,
+                                    index: i + 1,
+                                    monitor: monitor_number(&display_bounds, win),
+                                });
+                                return list_row(
+                                    i,
+                                    label,
+                                    label_size * (14.0 / 12.0),
+                                    icon_cache.get(&win.app_name).cloned(),
+                                    sibling_counts.get(win.app_name.as_str()).copied().filter(|n| *n > 1),
+                                    win.is_minimized,
+                                    is_selected.then_some(accent),
+                                    activate_on_click(entity.clone(), i),
+                                )
+                                .when(rtl, |s| s.flex_row_reverse().text_right())
+                                .when_some(current_ring, |s, ring| s.border_1().border_color(ring))
+                                .into_any_element();
+                            }
+                            // Grow around the center; negative margins keep the
+                            // layout slot unchanged so neighbours don't reflow.
+                            let grow = if hovered_card == Some(i) { hover_scale } else { 1.0 };
+                            let (dw, dh) = (card_w * (grow - 1.0), card_h * (grow - 1.0));
+                            let card = div()
                                 .id(ElementId::Integer(i as u64))
                                 .flex()
                                 .flex_col()
                                 .items_center()
-                                .w(px(GRID_CARD_WIDTH))
-                                .h(px(GRID_CARD_HEIGHT))
+                                .w(px(card_w + dw))
+                                .h(px(card_h + dh))
+                                .when(grow > 1.0, |s| s.mx(px(-dw / 2.0)).my(px(-dh / 2.0)))
                                 .p_2()
                                 .rounded_xl()
                                 .cursor_pointer()
-                                .on_click(move |_ev: &ClickEvent, window, cx| {
-                                    let window_id = entity_for_click
-                                        .update(cx, |this, cx| {
-                                            this.delegate.update(cx, |s, _cx| {
-                                                s.selected_index = Some(i);
-                                            });
-                                            this.delegate
-                                                .read(cx)
-                                                .windows
-                                                .get(i)
-                                                .map(|w| w.id)
-                                        })
-                                        .ok()
-                                        .flatten();
-                                    if let Some(_id) = window_id {
-                                        entity_for_click
-                                            .update(cx, |this, cx| {
-                                                this.delegate.update(cx, |s, _cx| {
-                                                    s.activate_selected(window);
-                                                });
-                                            })
-                                            .ok();
-                                    }
-                                })
+                                .on_click(activate_on_click(entity.clone(), i))
                                 .when(is_selected && !transparent_bg, |s| {
-                                    s.bg(rgb(0x233050)).border_1().border_color(rgb(0x4a6fa5))
+                                    s.bg(rgb(0x233050)).border_1().border_color(rgb(accent))
                                 })
                                 .when(is_selected && transparent_bg, |s| {
                                     s.bg(rgba(card_bg_rgba))
                                         .border_1()
-                                        .border_color(rgb(0x4a6fa5))
+                                        .border_color(rgb(accent))
                                 })
-                                .when(!is_selected && !transparent_bg, |s| {
-                                    s.bg(rgb(0x1a1e2a)).hover(|mut h| {
+                                .when(!is_selected && !transparent_bg, |s| s.bg(rgb(0x1a1e2a)))
+                                .when_some(current_ring, |s, ring| s.border_1().border_color(ring))
+                                .when(hover_scale > 1.0, |s| s.on_hover(hover_card(entity.clone(), i)))
+                                .hover(move |mut h| {
+                                    if !is_selected && !transparent_bg {
                                         h.background = Some(rgb(0x1e2640).into());
-                                        h
-                                    })
+                                    }
+                                    h
                                 })
                                 .when(!is_selected && transparent_bg, |s| {
                                     s.bg(rgba(card_bg_rgba))
                                 })
-                                .child(div().rounded_md().overflow_hidden().child(preview_tile(
+                                .when_some(selection_glow.filter(|_| is_selected), |s, (color, intensity)| {
+                                    s.border_2()
+                                        .border_color(rgb(color))
+                                        .shadow(glow_shadow(color, intensity))
+                                })
+                                .child(div()
+                                    .relative()
+                                    .w(px(preview_w * grow))
+                                    .h(px(preview_h * grow))
+                                    .rounded_md()
+                                    .overflow_hidden()
+                                    .child(preview_tile(
                                     live_previews.get(&win.id),
                                     &win.preview_path,
-                                    if win.is_minimized { icon_cache.get(&win.app_name) } else { None },
-                                    GRID_PREVIEW_WIDTH,
-                                    GRID_PREVIEW_HEIGHT,
-                                )))
+                                    if win.is_minimized
+                                        || !wants_preview[i]
+                                        || (win.is_off_space && !live_previews.contains_key(&win.id))
+                                        || d.capture_gave_up(win.id)
+                                    {
+                                        icon_cache.get(&win.app_name)
+                                    } else {
+                                        None
+                                    },
+                                    icon_placeholder.then(|| icon_cache.get(&win.app_name)).flatten(),
+                                    placeholder_image,
+                                ))
+                                .when(!win.is_minimized && wants_preview[i] && d.preview_is_stale(win.id), |el| {
+                                    el.child(div().absolute().inset_0().bg(rgba(0x0f111a66)))
+                                })
+                                .when_some(
+                                    app_counts
+                                        .and_then(|counts| counts.get(&win.app_name).copied())
+                                        .filter(|n| *n > 1),
+                                    |el, count| {
+                                        el.child(
+                                            div()
+                                                .absolute()
+                                                .top_1()
+                                                .right_1()
+                                                .px_1()
+                                                .rounded_md()
+                                                .bg(rgba(0x0f111acc))
+                                                .text_color(rgb(0xffffff))
+                                                .text_xs()
+                                                .child(format!("×{}", count)),
+                                        )
+                                    },
+                                ).when_some(
+                                    shortcuts.get(i).cloned(),
+                                    |el, key| {
+                                        let dimmed = !key.starts_with(hint_prefix);
+                                        el.child(
+                                            div()
+                                                .absolute()
+                                                .top_1()
+                                                .left_1()
+                                                .px_1()
+                                                .rounded_sm()
+                                                .bg(rgba(0x0f111a99))
+                                                .text_color(rgb(0xcfd6e6))
+                                                .text_size(px(10.0))
+                                                .when(dimmed, |s| s.opacity(0.3))
+                                                .child(key),
+                                        )
+                                    },
+                                ))
                                 .child({
-                                    let label = label_config.format(&win.app_name, &win.title);
+                                    let label = label_config.format(&LabelFields {
+                                        app_name: &win.app_name,
+                                        title: &win.title,
+                                        index: i + 1,
+                                        monitor: monitor_number(&display_bounds, win),
+                                    });
                                     let label_text = if show_debug_overlay {
                                         format!("[{}] {}", i, label)
                                     } else {
                                         label
                                     };
                                     let app_icon = icon_cache.get(&win.app_name).cloned();
+                                    let badge = app_badges.get(&win.app_name).cloned();
                                     div()
                                         .mt_2()
                                         .w_full()
                                         .flex()
                                         .flex_row()
+                                        .when(rtl, |s| s.flex_row_reverse().text_right())
                                         .items_center()
                                         .gap_1()
                                         .px_1()
@@ -194,23 +437,288 @@ impl Render for AltTabApp {
                                         })
                                         .when_some(app_icon, |el, icon| {
                                             el.child(
-                                                img(icon)
-                                                    .w(px(16.0))
-                                                    .h(px(16.0))
-                                                    .rounded_sm()
-                                                    .flex_shrink_0(),
+                                                div()
+                                                    .relative()
+                                                    .flex_shrink_0()
+                                                    .child(img(icon).w(px(16.0)).h(px(16.0)).rounded_sm())
+                                                    .when_some(badge, |el, badge| {
+                                                        el.child(
+                                                            div()
+                                                                .absolute()
+                                                                .top(px(-6.0))
+                                                                .left(px(10.0))
+                                                                .px(px(3.0))
+                                                                .rounded_full()
+                                                                .bg(rgb(0xe5484d))
+                                                                .text_color(rgb(0xffffff))
+                                                                .text_size(px(9.0))
+                                                                .line_height(px(12.0))
+                                                                .child(badge),
+                                                        )
+                                                    }),
                                             )
                                         })
+                                        .when_some(
+                                            sibling_counts.get(win.app_name.as_str()).copied().filter(|n| *n > 1),
+                                            |el, count| el.child(window_count_tag(count)),
+                                        )
                                         .child(
                                             div()
-                                                .text_xs()
+                                                .text_size(label_size)
                                                 .text_ellipsis()
                                                 .overflow_hidden()
                                                 .child(label_text),
                                         )
-                                })
+                                        .when_some(d.cwd_badge(win), |el, project| {
+                                            el.child(
+                                                div()
+                                                    .flex_shrink_0()
+                                                    .px_1()
+                                                    .rounded_sm()
+                                                    .bg(rgb(0x2a3145))
+                                                    .text_color(rgb(0x9fb1cf))
+                                                    .text_size(px(10.0))
+                                                    .child(project),
+                                            )
+                                        })
+                                        .when(win.has_unsaved_changes, |el| {
+                                            el.child(
+                                                div()
+                                                    .flex_shrink_0()
+                                                    .size(px(6.0))
+                                                    .rounded_full()
+                                                    .bg(rgb(0xe0b060)),
+                                            )
+                                        })
+                                });
+                            match selection_glow.filter(|_| is_selected && glow_pulse) {
+                                Some((color, intensity)) => card
+                                    .with_animation(
+                                        "selection-glow",
+                                        Animation::new(Duration::from_millis(GLOW_PULSE_MS))
+                                            .repeat()
+                                            .with_easing(pulsating_between(0.4, 1.0)),
+                                        move |card, delta| card.shadow(glow_shadow(color, intensity * delta)),
+                                    )
+                                    .into_any_element(),
+                                None => card.into_any_element(),
+                            }
                         }))
+                })
+                .when_some(peek_image, |s, image| {
+                    s.child(
+                        // ── Peek overlay ──────────────────────────────────────
+                        div()
+                            .absolute()
+                            .inset_0()
+                            .p_4()
+                            .bg(rgba(0x0f111ae6))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .child(
+                                img(image)
+                                    .w_full()
+                                    .h_full()
+                                    .object_fit(ObjectFit::Contain)
+                                    .rounded_md(),
+                            ),
+                    )
                 }),
             )
+            .when(show_action_bar, |s| {
+                s.child(
+                    // ── Quick action bar ──────────────────────────────────────────
+                    div()
+                        .px_4()
+                        .py_2()
+                        .border_t_1()
+                        .border_color(rgb(0x1e2333))
+                        .bg(rgb(0x13151f))
+                        .flex()
+                        .items_center()
+                        .justify_end()
+                        .gap_2()
+                        .child(action_button("action-close", "Close (W)", cx.listener(
+                            |this, _: &ClickEvent, _window, cx| super::input::close_selected(this, cx),
+                        )))
+                        .child(action_button("action-minimize", "Minimize (R)", cx.listener(
+                            |this, _: &ClickEvent, _window, cx| super::input::minimize_selected(this, cx),
+                        )))
+                        .child(action_button("action-quit", "Quit (Q)", cx.listener(
+                            |this, _: &ClickEvent, _window, cx| super::input::quit_selected(this, cx),
+                        ))),
+                )
+            })
     }
 }
+
+/// Soft outer glow in `color` (0xRRGGBB) at `intensity` (0.0-1.0).
+fn glow_shadow(color: u32, intensity: f32) -> Vec<BoxShadow> {
+    let alpha = (intensity.clamp(0.0, 1.0) * 255.0) as u32;
+    vec![BoxShadow {
+        color: rgba((color << 8) | alpha).into(),
+        offset: point(px(0.0), px(0.0)),
+        blur_radius: px(6.0 + 14.0 * intensity),
+        spread_radius: px(1.0 + 2.0 * intensity),
+    }]
+}
+
+/// Select card `i` and activate it.
+fn activate_on_click(
+    entity: WeakEntity<AltTabApp>,
+    i: usize,
+) -> impl Fn(&ClickEvent, &mut Window, &mut App) + 'static {
+    move |_ev: &ClickEvent, window, cx| {
+        let window_id = entity
+            .update(cx, |this, cx| {
+                this.delegate.update(cx, |s, _cx| {
+                    s.selected_index = Some(i);
+                });
+                this.delegate.read(cx).windows.get(i).map(|w| w.id)
+            })
+            .ok()
+            .flatten();
+        if let Some(_id) = window_id {
+            entity
+                .update(cx, |this, cx| this.activate_selected(window, cx))
+                .ok();
+        }
+    }
+}
+
+/// Track the card under the mouse for `hover_scale`. Style-only hover
+/// can't resize an element, so the size is applied in the next layout.
+fn hover_card(
+    entity: WeakEntity<AltTabApp>,
+    i: usize,
+) -> impl Fn(&bool, &mut Window, &mut App) + 'static {
+    move |hovered: &bool, _window, cx| {
+        let _ = entity.update(cx, |this, cx| {
+            let next = match (*hovered, this.hovered_card) {
+                (true, _) => Some(i),
+                (false, Some(ix)) if ix == i => None,
+                (false, current) => current,
+            };
+            if next != this.hovered_card {
+                this.hovered_card = next;
+                cx.notify();
+            }
+        });
+    }
+}
+
+/// One row of the list layout: app icon and label, no preview.
+fn list_row(
+    i: usize,
+    label: String,
+    label_size: Pixels,
+    icon: Option<std::sync::Arc<RenderImage>>,
+    sibling_count: Option<usize>,
+    is_minimized: bool,
+    selected_accent: Option<u32>,
+    on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+) -> Stateful<Div> {
+    let is_selected = selected_accent.is_some();
+    div()
+        .id(ElementId::Integer(i as u64))
+        .w_full()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .cursor_pointer()
+        .on_click(on_click)
+        .when_some(selected_accent, |s, accent| {
+            s.bg(rgb(0x233050)).border_1().border_color(rgb(accent))
+        })
+        .when(!is_selected, |s| s.hover(|h| h.bg(rgb(0x1e2640))))
+        .text_color(if is_selected { rgb(0xffffff) } else { rgb(0x7a849e) })
+        .when_some(icon, |el, icon| {
+            el.child(img(icon).w(px(16.0)).h(px(16.0)).rounded_sm().flex_shrink_0())
+        })
+        .when_some(sibling_count, |el, count| el.child(window_count_tag(count)))
+        .child(
+            div()
+                .flex_1()
+                .text_size(label_size)
+                .text_ellipsis()
+                .overflow_hidden()
+                .child(label),
+        )
+        .when(is_minimized, |el| {
+            el.child(div().text_xs().text_color(rgb(0x5e6a84)).child("minimized"))
+        })
+}
+
+/// `show_app_window_count`: "(N)" beside the app icon.
+fn window_count_tag(count: usize) -> Div {
+    div()
+        .flex_shrink_0()
+        .text_size(px(10.0))
+        .text_color(rgb(0x5e6a84))
+        .child(format!("({})", count))
+}
+
+fn action_button(
+    id: &'static str,
+    label: &'static str,
+    on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+) -> impl IntoElement {
+    div()
+        .id(id)
+        .px_3()
+        .py_1()
+        .rounded_md()
+        .text_xs()
+        .text_color(rgb(0x9fb1cf))
+        .bg(rgb(0x1e2333))
+        .hover(|s| s.bg(rgb(0x2a3145)))
+        .cursor_pointer()
+        .child(label)
+        .on_click(on_click)
+}
+
+/// Header hint text for the keys that matter in the current mode.
+fn hotkey_hints(mode: &ActionMode, modifier: Modifier, vim_navigation: bool) -> String {
+    let commit = match mode {
+        ActionMode::HoldToSwitch => format!("release {} switch", modifier.key_name()),
+        ActionMode::Sticky => "⏎ switch".to_string(),
+    };
+    let (navigate, layout) = if vim_navigation {
+        ("hjkl/↑↓←→ navigate", "⇧L grid/list")
+    } else {
+        ("↑↓←→ navigate", "L grid/list")
+    };
+    [
+        navigate,
+        commit.as_str(),
+        "Space peek",
+        "W close",
+        "Q quit",
+        "R minimize",
+        "M toggle minimized",
+        layout,
+        "esc close",
+    ]
+    .join("  ·  ")
+}
+
+/// 1-based index of the display holding most of the window (1 if none overlap).
+fn monitor_number(displays: &[Bounds<Pixels>], win: &WindowInfo) -> usize {
+    let monitors: Vec<(f32, f32, f32, f32)> = displays
+        .iter()
+        .map(|b| {
+            (
+                b.origin.x.to_f64() as f32,
+                b.origin.y.to_f64() as f32,
+                b.size.width.to_f64() as f32,
+                b.size.height.to_f64() as f32,
+            )
+        })
+        .collect();
+    monitor_for_bounds((win.x, win.y, win.width, win.height), &monitors).map_or(1, |ix| ix + 1)
+}
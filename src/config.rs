@@ -1,6 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct DisplayConfig {
     pub max_columns: usize,
@@ -10,6 +13,84 @@ pub struct DisplayConfig {
     pub show_minimized: bool,
     pub show_debug_overlay: bool,
     pub show_hotkey_hints: bool,
+    pub preview_quality: PreviewQuality,
+    pub picker_anchor: PickerAnchor,
+    /// Layout the picker opens in; `L` switches it while open.
+    pub layout: LayoutMode,
+    /// Card order: window stacking order, or most recently focused first.
+    pub order: WindowOrder,
+    /// Card flow and label alignment; `auto` follows the locale.
+    pub direction: TextDirection,
+    /// Grow the card under the mouse by this factor (1.0 = off).
+    pub hover_scale: f32,
+    /// Size of cards and previews relative to the default (0.5-2.0).
+    pub card_scale: f32,
+    /// Fixed picker position per monitor, keyed by monitor origin (`"x,y"`) or `"default"`.
+    pub picker_offsets: HashMap<String, PickerOffset>,
+    /// Fade-out duration when the picker is cancelled; 0 hides instantly.
+    pub close_fade_ms: u64,
+    /// Sticky mode: close the picker after this many ms without a key press or mouse move.
+    pub auto_dismiss_ms: Option<u64>,
+    /// Sticky mode only: keep the picker above other windows while it is open.
+    pub always_on_top: bool,
+    /// macOS: also list windows that live on other Spaces.
+    pub include_other_spaces: bool,
+    /// Linux: list the desktop window (file-manager drawn desktop) as a target.
+    pub include_desktop: bool,
+    /// Only list windows whose center is on the monitor the picker opens on.
+    pub current_monitor_only: bool,
+    /// Drop cards whose preview capture keeps failing (never the frontmost or selected window).
+    pub hide_previewless_windows: bool,
+    /// Footer with Close / Minimize / Quit buttons for the selected window.
+    pub show_action_bar: bool,
+    /// Strip of the most recent windows above the grid for one-click switching.
+    pub show_recent_strip: bool,
+    /// Show the app icon on cards until their first preview arrives.
+    pub icon_placeholder: bool,
+    /// Image file shown on cards until their first preview arrives; wins over `icon_placeholder`.
+    pub placeholder_image: Option<String>,
+    /// Tint previews that have not been recaptured for a while, as a cue they may be out of date.
+    pub indicate_stale_previews: bool,
+    /// Corner badge on each card with the key that activates it.
+    #[serde(alias = "show_index_badges")]
+    pub show_shortcut_badges: bool,
+    /// Draw the mouse cursor into the preview of the window under it.
+    pub capture_cursor: bool,
+    /// macOS 14+: capture previews with ScreenCaptureKit instead of CoreGraphics.
+    pub screen_capture_kit: bool,
+    /// macOS: show Dock badges (e.g. unread counts) on card icons.
+    pub show_app_badges: bool,
+    /// Show how many windows a card's app has, e.g. "(4)", when it has more than one.
+    pub show_app_window_count: bool,
+    /// Windows larger than this many logical pixels are live-captured less
+    /// often so one huge window can't starve the rest. 0 disables the limit.
+    pub large_window_capture_pixels: u64,
+    /// Memory budget for cached previews in MB; least recently shown ones are dropped past it. 0 = unlimited.
+    pub preview_memory_mb: u32,
+    /// Capture only the selected window; other cards show their app icon.
+    pub preview_selected_only: bool,
+    /// Apps (by name, case-insensitive) whose windows keep live previews in
+    /// `preview_selected_only` mode.
+    pub always_preview_apps: Vec<String>,
+    /// Refresh rate for the selected card's preview; 0 keeps the normal cadence.
+    pub stream_selected_fps: u32,
+    /// Minimum ms between refreshes of a preview that changes every round
+    /// (video, animation). The selected card is exempt. 0 disables the limit.
+    pub animated_preview_interval_ms: u64,
+    /// Font for labels and header text; GPUI's default when unset.
+    pub font_family: Option<String>,
+    /// Tried in order for glyphs the main font lacks (CJK, emoji).
+    pub font_fallbacks: Vec<String>,
+    /// Label text size multiplier (0.75-1.5).
+    pub font_scale: f32,
+    /// Take the selection border/glow color from the OS accent when available.
+    pub use_system_accent: bool,
+    /// Outer glow around the selected card (6-char hex, no `#`).
+    pub selection_glow_color: String,
+    /// Glow strength 0.0-1.0; 0 keeps the flat 1px highlight.
+    pub selection_glow_intensity: f32,
+    /// Pulse the glow; static when the OS asks for reduced motion.
+    pub selection_glow_pulse: bool,
 }
 
 impl Default for DisplayConfig {
@@ -22,17 +103,246 @@ impl Default for DisplayConfig {
             show_minimized: true,
             show_debug_overlay: false,
             show_hotkey_hints: true,
+            preview_quality: PreviewQuality::default(),
+            picker_anchor: PickerAnchor::default(),
+            layout: LayoutMode::default(),
+            order: WindowOrder::default(),
+            direction: TextDirection::default(),
+            hover_scale: 1.0,
+            card_scale: 1.0,
+            picker_offsets: HashMap::new(),
+            close_fade_ms: 0,
+            auto_dismiss_ms: None,
+            always_on_top: false,
+            include_other_spaces: false,
+            include_desktop: false,
+            current_monitor_only: false,
+            hide_previewless_windows: false,
+            show_action_bar: false,
+            show_recent_strip: false,
+            icon_placeholder: true,
+            placeholder_image: None,
+            indicate_stale_previews: false,
+            show_shortcut_badges: false,
+            capture_cursor: false,
+            screen_capture_kit: false,
+            show_app_badges: false,
+            show_app_window_count: false,
+            large_window_capture_pixels: 4_000_000,
+            preview_memory_mb: 0,
+            preview_selected_only: false,
+            always_preview_apps: Vec::new(),
+            stream_selected_fps: 0,
+            animated_preview_interval_ms: 2000,
+            font_family: None,
+            font_fallbacks: Vec::new(),
+            font_scale: 1.0,
+            use_system_accent: false,
+            selection_glow_color: "4a6fa5".to_string(),
+            selection_glow_intensity: 0.35,
+            selection_glow_pulse: false,
         }
     }
 }
 
+impl DisplayConfig {
+    /// Configured offset for the monitor whose top-left corner is at `origin`.
+    pub fn picker_offset_for(&self, origin: (f32, f32)) -> Option<PickerOffset> {
+        let key = format!("{},{}", origin.0.round() as i32, origin.1.round() as i32);
+        self.picker_offsets
+            .get(&key)
+            .or_else(|| self.picker_offsets.get("default"))
+            .copied()
+    }
+
+    /// Card fill as `0xRRGGBB` plus clamped opacity, falling back to the default color.
+    pub fn card_bg(&self) -> (u32, f32) {
+        let (r, g, b) = parse_hex_color(&self.card_background_color).unwrap_or((0x1a, 0x1e, 0x2a));
+        let color = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
+        let opacity = self.card_background_opacity.clamp(0.0, 1.0);
+        (color, opacity)
+    }
+
+    /// `preview_memory_mb` in bytes, or None when unlimited.
+    pub fn preview_memory_budget(&self) -> Option<usize> {
+        (self.preview_memory_mb > 0).then(|| self.preview_memory_mb as usize * 1024 * 1024)
+    }
+
+    /// `card_scale` clamped to the supported range.
+    pub fn card_scale(&self) -> f32 {
+        self.card_scale.clamp(0.5, 2.0)
+    }
+
+    /// `placeholder_image` with `~/` expanded; None when unset or blank.
+    pub fn placeholder_image_path(&self) -> Option<PathBuf> {
+        let raw = self.placeholder_image.as_deref()?.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        match raw.strip_prefix("~/") {
+            Some(rest) => Some(dirs::home_dir()?.join(rest)),
+            None => Some(PathBuf::from(raw)),
+        }
+    }
+
+    /// Whether `app_name` is on the `always_preview_apps` allowlist.
+    pub fn always_previews(&self, app_name: &str) -> bool {
+        self.always_preview_apps
+            .iter()
+            .any(|app| app.eq_ignore_ascii_case(app_name))
+    }
+
+    /// Selection glow as `0xRRGGBB` plus clamped intensity; `None` when disabled.
+    pub fn selection_glow(&self) -> Option<(u32, f32)> {
+        let intensity = self.selection_glow_intensity.clamp(0.0, 1.0);
+        if intensity <= 0.0 {
+            return None;
+        }
+        let (r, g, b) = parse_hex_color(&self.selection_glow_color).unwrap_or((0x4a, 0x6f, 0xa5));
+        Some((((r as u32) << 16) | ((g as u32) << 8) | (b as u32), intensity))
+    }
+}
+
 pub use qol_plugin_api::color::parse_hex_color;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Downscale filter used when turning a captured window into a thumbnail.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewQuality {
+    /// Nearest-neighbor sampling: cheapest, but blocky on text.
+    #[default]
+    Fast,
+    /// Box (area-average) filter.
+    Balanced,
+    /// Lanczos3 resampling: crispest, and by far the most CPU per capture.
+    Sharp,
+}
+
+/// Card grid with previews, or a dense one-row-per-window list.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    #[default]
+    Grid,
+    List,
+}
+
+/// Order of the cards in the picker.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowOrder {
+    /// Window stacking order as reported by the platform.
+    #[default]
+    ZOrder,
+    /// Most recently focused first; windows never seen focused follow in stacking order.
+    Mru,
+    /// By title, case-insensitive.
+    AlphabeticalByTitle,
+    /// By app name, then title, case-insensitive; an app's windows stay together.
+    AlphabeticalByApp,
+}
+
+/// Keys that activate a card directly.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickSelect {
+    None,
+    /// Digits 1-9 for the first nine cards.
+    #[default]
+    Numbers,
+    /// Home-row letter hints (`a`, `s`, … then `aa`, `as`, …) for every card.
+    HomeRow,
+}
+
+/// Left-to-right or right-to-left card flow.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+    /// Rtl when the locale language is written right-to-left.
+    Auto,
+}
+
+/// Languages written right-to-left, by ISO 639-1 code.
+const RTL_LANGUAGES: &[&str] = &["ar", "dv", "fa", "he", "ku", "ps", "sd", "ug", "ur", "yi"];
+
+impl TextDirection {
+    pub fn is_rtl(self) -> bool {
+        match self {
+            Self::Ltr => false,
+            Self::Rtl => true,
+            Self::Auto => ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.is_empty())
+                .is_some_and(|locale| {
+                    let lang = locale.split(['_', '.', '@', '-']).next().unwrap_or("");
+                    RTL_LANGUAGES.contains(&lang)
+                }),
+        }
+    }
+}
+
+impl LayoutMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Grid => Self::List,
+            Self::List => Self::Grid,
+        }
+    }
+}
+
+/// Where the picker is placed on the active monitor.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PickerAnchor {
+    #[default]
+    Center,
+    /// Centered on the mouse cursor, clamped so the picker stays on-screen.
+    CursorCentered,
+}
+
+/// Picker top-left corner relative to the monitor's top-left corner.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+pub struct PickerOffset {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct LabelConfig {
     pub show_app_name: bool,
     pub show_window_title: bool,
+    /// Overrides the booleans when set. Placeholders: `{app}`, `{title}`,
+    /// `{index}` (1-based), `{monitor}` (1-based); `{{`/`}}` for literal braces.
+    /// Unknown placeholders are kept as written.
+    pub template: Option<String>,
+    /// Shorten titles longer than this: drop a trailing app-name suffix first,
+    /// then cut the middle with "…".
+    pub max_title_chars: Option<usize>,
+    /// Where a label longer than `max_label_chars` is cut.
+    pub truncate: TruncateMode,
+    /// Labels are cut to this many characters, "…" included. None or 0 is
+    /// unlimited: `End` then leaves the cut to the card width, `Middle` uses
+    /// `DEFAULT_MIDDLE_LABEL_CHARS`.
+    pub max_label_chars: Option<usize>,
+}
+
+/// Cut applied in `Middle` mode when `max_label_chars` is unlimited; the card
+/// only ellipsizes the end, so the middle cut needs a length of its own.
+const DEFAULT_MIDDLE_LABEL_CHARS: usize = 80;
+
+/// Which end of an over-long label survives. `Middle` keeps both the start
+/// and the trailing " — App Name" that browser tabs share.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncateMode {
+    #[default]
+    End,
+    Middle,
 }
 
 impl Default for LabelConfig {
@@ -40,12 +350,53 @@ impl Default for LabelConfig {
         Self {
             show_app_name: true,
             show_window_title: true,
+            template: None,
+            max_title_chars: None,
+            truncate: TruncateMode::End,
+            max_label_chars: None,
         }
     }
 }
 
+/// Values substituted into a label template.
+pub struct LabelFields<'a> {
+    pub app_name: &'a str,
+    pub title: &'a str,
+    pub index: usize,
+    pub monitor: usize,
+}
+
 impl LabelConfig {
-    pub fn format(&self, app_name: &str, title: &str) -> String {
+    pub fn format(&self, fields: &LabelFields) -> String {
+        let label = self.untruncated(fields);
+        match self.label_limit() {
+            Some(max) => truncate_chars(&label, max, self.truncate),
+            None => label,
+        }
+    }
+
+    fn label_limit(&self) -> Option<usize> {
+        match (self.max_label_chars.filter(|max| *max > 0), self.truncate) {
+            (Some(max), _) => Some(max),
+            (None, TruncateMode::End) => None,
+            (None, TruncateMode::Middle) => Some(DEFAULT_MIDDLE_LABEL_CHARS),
+        }
+    }
+
+    fn untruncated(&self, fields: &LabelFields) -> String {
+        let shortened = self
+            .max_title_chars
+            .map(|max| shorten_title(fields.title, fields.app_name, max));
+        let fields = LabelFields {
+            title: shortened.as_deref().unwrap_or(fields.title),
+            ..*fields
+        };
+        if let Some(template) = &self.template {
+            if let Ok(label) = render_template(template, &fields) {
+                return label;
+            }
+        }
+        let (app_name, title) = (fields.app_name, fields.title);
         let show_app = self.show_app_name && !app_name.is_empty();
         let show_title = self.show_window_title && !title.is_empty();
         match (show_app, show_title) {
@@ -55,6 +406,170 @@ impl LabelConfig {
             (false, false) => String::new(),
         }
     }
+
+    /// Drops a template that does not parse so `format` uses the booleans.
+    fn validate(&mut self) {
+        let Some(template) = &self.template else {
+            return;
+        };
+        let sample = LabelFields { app_name: "", title: "", index: 0, monitor: 0 };
+        if let Err(e) = render_template(template, &sample) {
+            eprintln!("[alt-tab] invalid label.template {:?}: {}; using show_app_name/show_window_title", template, e);
+            self.template = None;
+        }
+    }
+}
+
+/// A template split into literal text and substituted values.
+enum Piece {
+    Text(String),
+    Value(String),
+}
+
+fn render_template(template: &str, fields: &LabelFields) -> Result<String, String> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => name.push(ch),
+                        None => return Err("unclosed '{'".to_string()),
+                    }
+                }
+                let value = match name.as_str() {
+                    "app" => capitalize_first(fields.app_name),
+                    "title" => fields.title.to_string(),
+                    "index" => fields.index.to_string(),
+                    "monitor" => fields.monitor.to_string(),
+                    other => {
+                        text.push('{');
+                        text.push_str(other);
+                        text.push('}');
+                        continue;
+                    }
+                };
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(Piece::Value(value));
+            }
+            '}' => return Err("unmatched '}'".to_string()),
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    Ok(join_pieces(pieces))
+}
+
+/// Concatenate, dropping the separator an empty value would leave dangling:
+/// `"{app} - {title}"` with no title renders `"App"`, not `"App - "`.
+fn join_pieces(pieces: Vec<Piece>) -> String {
+    let mut kept: Vec<Piece> = Vec::with_capacity(pieces.len());
+    let mut after_empty = false;
+    for piece in pieces {
+        match piece {
+            Piece::Value(v) if v.is_empty() => after_empty = true,
+            Piece::Value(v) => {
+                kept.push(Piece::Value(v));
+                after_empty = false;
+            }
+            Piece::Text(t) => {
+                let dangling = after_empty
+                    && is_separator(&t)
+                    && match kept.last() {
+                        None => true,
+                        Some(Piece::Text(prev)) => is_separator(prev),
+                        Some(Piece::Value(_)) => false,
+                    };
+                if !dangling {
+                    kept.push(Piece::Text(t));
+                    after_empty = false;
+                }
+            }
+        }
+    }
+    if after_empty && matches!(kept.last(), Some(Piece::Text(t)) if is_separator(t)) {
+        kept.pop();
+    }
+    kept.into_iter()
+        .map(|p| match p {
+            Piece::Text(s) | Piece::Value(s) => s,
+        })
+        .collect()
+}
+
+/// Whitespace and punctuation only, like `" - "` or `": "`.
+fn is_separator(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_whitespace() || "-–—|:;,.·•/".contains(c))
+}
+
+const TITLE_SEPARATORS: [&str; 4] = [" - ", " — ", " – ", " | "];
+
+fn shorten_title(title: &str, app_name: &str, max_chars: usize) -> String {
+    if title.chars().count() <= max_chars {
+        return title.to_string();
+    }
+    let title = strip_app_suffix(title, app_name);
+    truncate_chars(title, max_chars, TruncateMode::Middle)
+}
+
+/// `text` cut to at most `max_chars` characters, with "…" marking the cut.
+fn truncate_chars(text: &str, max_chars: usize, mode: TruncateMode) -> String {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let keep = max_chars - 1;
+    let (head, tail) = match mode {
+        TruncateMode::End => (keep, 0),
+        TruncateMode::Middle => (keep.div_ceil(2), keep / 2),
+    };
+    let mut out: String = text.chars().take(head).collect();
+    out.push('…');
+    out.extend(text.chars().skip(len - tail));
+    out
+}
+
+/// "Page - Mozilla Firefox" -> "Page" when the last segment names the app.
+fn strip_app_suffix<'a>(title: &'a str, app_name: &str) -> &'a str {
+    let app = app_name.to_lowercase();
+    if app.is_empty() {
+        return title;
+    }
+    let Some((ix, sep)) = TITLE_SEPARATORS
+        .iter()
+        .filter_map(|sep| title.rfind(sep).map(|ix| (ix, *sep)))
+        .max_by_key(|(ix, _)| *ix)
+    else {
+        return title;
+    };
+    let suffix = title[ix + sep.len()..].trim().to_lowercase();
+    if !suffix.is_empty() && (suffix.contains(&app) || app.contains(&suffix)) && ix > 0 {
+        &title[..ix]
+    } else {
+        title
+    }
 }
 
 fn capitalize_first(s: &str) -> String {
@@ -65,7 +580,18 @@ fn capitalize_first(s: &str) -> String {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+/// Placement of minimized windows in the list.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimizedSort {
+    /// After every visible window.
+    #[default]
+    Last,
+    /// Among visible windows by when each was last focused.
+    ByMru,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ActionMode {
     Sticky,
@@ -73,25 +599,192 @@ pub enum ActionMode {
     HoldToSwitch,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+/// How a minimized window is brought back when activated.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimizedActivate {
+    /// Un-minimize with the system animation.
+    #[default]
+    Restore,
+    /// Skip the un-minimize animation where the platform allows it (X11).
+    RestoreNoAnimation,
+}
+
+/// Modifier whose release ends a hold-to-switch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Modifier {
+    /// Alt, or Option on macOS.
+    #[default]
+    Alt,
+    Control,
+    /// Command on macOS, the Super/Windows key elsewhere.
+    Command,
+    Shift,
+}
+
+impl Modifier {
+    /// The key's name as printed on this platform's keyboards.
+    pub fn key_name(self) -> &'static str {
+        match self {
+            Modifier::Alt if cfg!(target_os = "macos") => "Option",
+            Modifier::Alt => "Alt",
+            Modifier::Control => "Ctrl",
+            Modifier::Command if cfg!(target_os = "macos") => "Cmd",
+            Modifier::Command => "Super",
+            Modifier::Shift => "Shift",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum OpenBehavior {
     #[default]
     CycleOnce,
     ShowOnly,
+    /// Select the window last activated from the picker, if it is still open.
+    RestoreLast,
+}
+
+/// What Enter does when the picker has no windows to activate.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyEnterAction {
+    #[default]
+    None,
+    Close,
+    /// Run a shell command (`sh -c`, or `cmd /C` on Windows), then close.
+    RunCommand(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct AltTabConfig {
     pub display: DisplayConfig,
     pub action_mode: ActionMode,
+    /// Hold-to-switch: the key held while cycling; releasing it switches.
+    #[serde(default)]
+    pub action_modifier: Modifier,
     #[serde(default = "default_reset_selection_on_open")]
     pub reset_selection_on_open: bool,
+    /// Leave the window that was focused when the picker opened out of the grid.
+    #[serde(default)]
+    pub exclude_current_window: bool,
     #[serde(default)]
     pub open_behavior: OpenBehavior,
+    /// Show-reverse with no picker open opens one with the last window selected.
+    #[serde(default)]
+    pub reverse_can_open: bool,
+    #[serde(default)]
+    pub empty_enter_action: EmptyEnterAction,
+    /// Keep a hidden 1x1 window open so the daemon survives without a picker.
+    /// Only honoured as `false` on macOS; other platforms need it.
+    #[serde(default = "default_use_keepalive_window")]
+    pub use_keepalive_window: bool,
+    /// Tab / Shift+Tab wrap around at the list ends; false stops at the first/last window.
+    #[serde(default = "default_cycle_wrap")]
+    pub cycle_wrap: bool,
+    /// One card per app (its frontmost window); `E` expands the selected app.
+    #[serde(default)]
+    pub collapse_apps: bool,
+    /// Where minimized windows go in the list.
+    #[serde(default)]
+    pub minimized_sort: MinimizedSort,
+    /// How activating a minimized window restores it.
+    #[serde(default)]
+    pub minimized_activate: MinimizedActivate,
     #[serde(default)]
     pub label: LabelConfig,
+    /// Move the activated window onto the monitor the picker opened on.
+    #[serde(default)]
+    pub activate_to_active_monitor: bool,
+    /// Hold-to-switch: releasing Alt sooner than this after opening cancels instead of switching.
+    #[serde(default)]
+    pub activation_debounce_ms: u64,
+    /// Hold-to-switch: the picker stays up at least this long before switching, so it never flashes.
+    #[serde(default = "default_min_visible_ms")]
+    pub min_visible_ms: u64,
+    /// Hold-to-switch: a quick Alt+Tab tap switches to the previous window without showing the grid.
+    #[serde(default)]
+    pub tap_hold_discriminate: bool,
+    /// Longest Alt hold (ms) that still counts as a tap.
+    #[serde(default = "default_tap_max_ms")]
+    pub tap_max_ms: u64,
+    /// Tap switch skips other windows of the frontmost app and lands on a different app.
+    #[serde(default)]
+    pub flip_skips_same_app: bool,
+    /// h/j/k/l move the selection like the arrow keys.
+    #[serde(default)]
+    pub vim_navigation: bool,
+    /// Keys that jump straight to a card: digits 1-9, or home-row hints for any count.
+    #[serde(default)]
+    pub quick_select: QuickSelect,
+    /// Named partial configs layered over this one; switch with `--profile <name>`.
+    #[serde(default)]
+    pub profiles: HashMap<String, serde_json::Value>,
+    /// Apps whose cards get a badge with the working directory found in the title.
+    #[serde(default)]
+    pub group_by_cwd_for_apps: Vec<String>,
+    /// Regex locating the directory in those titles (first capture group); a path heuristic when unset.
+    #[serde(default)]
+    pub cwd_title_pattern: Option<String>,
+    /// Named window filters; `--group <name>` opens the picker showing only matches.
+    #[serde(default)]
+    pub smart_groups: HashMap<String, FilterSpec>,
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+}
+
+/// Settings read once when the daemon starts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DaemonConfig {
+    /// File that gets a JSON line per activation (`-` for stdout); off when unset.
+    pub event_log: Option<String>,
+}
+
+impl DaemonConfig {
+    /// `event_log` with a leading `~/` expanded; None when unset or blank.
+    pub fn event_log_path(&self) -> Option<PathBuf> {
+        let raw = self.event_log.as_deref()?.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        match raw.strip_prefix("~/") {
+            Some(rest) => Some(dirs::home_dir()?.join(rest)),
+            None => Some(PathBuf::from(raw)),
+        }
+    }
+}
+
+/// Which windows belong to a smart group. Patterns are case-insensitive
+/// substrings; a window matches if its app name matches any of `apps` or its
+/// title matches any of `titles`. An empty spec matches nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FilterSpec {
+    pub apps: Vec<String>,
+    pub titles: Vec<String>,
+    /// Exact app name, for `--show-app`; never read from config.
+    #[serde(skip)]
+    pub exact_app: Option<String>,
+}
+
+impl FilterSpec {
+    /// Only the windows of `app_name` (exact match).
+    pub fn for_app(app_name: &str) -> Self {
+        Self { exact_app: Some(app_name.to_string()), ..Self::default() }
+    }
+
+    pub fn matches(&self, app_name: &str, title: &str) -> bool {
+        let contains = |haystack: &str, pattern: &String| {
+            !pattern.is_empty() && haystack.to_lowercase().contains(&pattern.to_lowercase())
+        };
+        self.exact_app.as_deref() == Some(app_name)
+            || self.apps.iter().any(|p| contains(app_name, p))
+            || self.titles.iter().any(|p| contains(title, p))
+    }
 }
 
 impl Default for AltTabConfig {
@@ -99,21 +792,95 @@ impl Default for AltTabConfig {
         Self {
             display: DisplayConfig::default(),
             action_mode: ActionMode::default(),
+            action_modifier: Modifier::default(),
             reset_selection_on_open: default_reset_selection_on_open(),
+            exclude_current_window: false,
             open_behavior: OpenBehavior::default(),
+            reverse_can_open: false,
+            empty_enter_action: EmptyEnterAction::default(),
+            use_keepalive_window: default_use_keepalive_window(),
+            cycle_wrap: default_cycle_wrap(),
+            collapse_apps: false,
+            minimized_sort: MinimizedSort::default(),
+            minimized_activate: MinimizedActivate::default(),
             label: LabelConfig::default(),
+            activate_to_active_monitor: false,
+            activation_debounce_ms: 0,
+            min_visible_ms: default_min_visible_ms(),
+            tap_hold_discriminate: false,
+            tap_max_ms: default_tap_max_ms(),
+            flip_skips_same_app: false,
+            vim_navigation: false,
+            quick_select: QuickSelect::default(),
+            profiles: HashMap::new(),
+            group_by_cwd_for_apps: Vec::new(),
+            cwd_title_pattern: None,
+            smart_groups: HashMap::new(),
+            daemon: DaemonConfig::default(),
         }
     }
 }
 
+fn default_tap_max_ms() -> u64 {
+    150
+}
+
+fn default_min_visible_ms() -> u64 {
+    60
+}
+
+impl AltTabConfig {
+    /// Whether a Show should first wait out the tap window (hold-to-switch only).
+    pub fn discriminates_taps(&self) -> bool {
+        self.tap_hold_discriminate && self.action_mode == ActionMode::HoldToSwitch && self.tap_max_ms > 0
+    }
+
+    /// `display.always_on_top` only applies in Sticky mode.
+    pub fn pin_on_top(&self) -> bool {
+        self.display.always_on_top && self.action_mode == ActionMode::Sticky
+    }
+
+    /// CycleOnce preselects the second card, skipping the current window. With
+    /// `exclude_current_window` that window isn't listed, so the first card
+    /// already is the previous one.
+    pub fn cycles_on_open(&self) -> bool {
+        self.open_behavior == OpenBehavior::CycleOnce && !self.exclude_current_window
+    }
+}
+
 fn default_reset_selection_on_open() -> bool {
     true
 }
 
+fn default_cycle_wrap() -> bool {
+    true
+}
+
+fn default_use_keepalive_window() -> bool {
+    true
+}
+
 const PLUGIN_NAMES: &[&str] = &["plugin-alt-tab", "alt-tab"];
 
+/// Profile selected at runtime; held by the daemon for its lifetime.
+static ACTIVE_PROFILE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Select the profile later loads resolve to; `None` (or `"default"`) uses the base config.
+pub fn set_active_profile(name: Option<String>) {
+    let name = name.filter(|n| n != "default");
+    eprintln!("[alt-tab] active profile: {}", name.as_deref().unwrap_or("default"));
+    if let Ok(mut active) = ACTIVE_PROFILE.lock() {
+        *active = name;
+    }
+}
+
 pub fn load_alt_tab_config() -> AltTabConfig {
-    let config: AltTabConfig = qol_plugin_api::config::load_plugin_config(PLUGIN_NAMES);
+    let mut config: AltTabConfig = qol_plugin_api::config::load_plugin_config(PLUGIN_NAMES);
+    let active = ACTIVE_PROFILE.lock().ok().and_then(|a| a.clone());
+    if let Some(name) = active {
+        config = config.with_profile(&name);
+    }
+    config.label.validate();
     eprintln!(
         "[alt-tab] config: action_mode={:?} max_columns={} reset_selection_on_open={} open_behavior={:?}",
         config.action_mode,
@@ -124,3 +891,129 @@ pub fn load_alt_tab_config() -> AltTabConfig {
     config
 }
 
+impl AltTabConfig {
+    /// This config with profile `name` deep-merged over it. An unknown profile
+    /// or one that doesn't deserialize is logged and the base config kept.
+    pub fn with_profile(self, name: &str) -> AltTabConfig {
+        let Some(overlay) = self.profiles.get(name).cloned() else {
+            eprintln!("[alt-tab] unknown profile '{}' — using base config", name);
+            return self;
+        };
+        let Ok(mut merged) = serde_json::to_value(&self) else {
+            return self;
+        };
+        merge_json(&mut merged, overlay);
+        match serde_json::from_value(merged) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("[alt-tab] invalid profile '{}': {} — using base config", name, e);
+                self
+            }
+        }
+    }
+}
+
+/// Recursively overlay `patch` onto `base`; objects merge, everything else replaces.
+fn merge_json(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// JSON Schema for `config.json`, for editors and the settings UI.
+pub fn config_schema_json() -> String {
+    let schema = schemars::schema_for!(AltTabConfig);
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(template: &str, app_name: &str, title: &str) -> String {
+        let config = LabelConfig { template: Some(template.to_string()), ..LabelConfig::default() };
+        config.format(&LabelFields { app_name, title, index: 3, monitor: 1 })
+    }
+
+    #[test]
+    fn template_substitutes_fields() {
+        assert_eq!(label("{index}. {app}: {title}", "firefox", "Inbox"), "3. Firefox: Inbox");
+    }
+
+    #[test]
+    fn template_escapes_doubled_braces() {
+        assert_eq!(label("{{{index}}} {title}", "firefox", "Inbox"), "{3} Inbox");
+    }
+
+    #[test]
+    fn unbalanced_template_is_rejected() {
+        let fields = LabelFields { app_name: "firefox", title: "Inbox", index: 3, monitor: 1 };
+        assert!(render_template("{app", &fields).is_err());
+        assert!(render_template("app}", &fields).is_err());
+    }
+
+    #[test]
+    fn invalid_template_falls_back_to_flags() {
+        let mut config = LabelConfig { template: Some("{app} - {title".to_string()), ..LabelConfig::default() };
+        config.validate();
+        assert!(config.template.is_none());
+        let fields = LabelFields { app_name: "firefox", title: "Inbox", index: 3, monitor: 1 };
+        assert_eq!(config.format(&fields), "Firefox - Inbox");
+    }
+
+    #[test]
+    fn empty_title_drops_its_separator() {
+        assert_eq!(label("{app} - {title}", "firefox", ""), "Firefox");
+        assert_eq!(label("{index}. {app}: {title}", "firefox", ""), "3. Firefox");
+    }
+
+    #[test]
+    fn empty_app_drops_its_separator() {
+        assert_eq!(label("{app} - {title}", "", "Inbox"), "Inbox");
+        assert_eq!(label("{index}. {app}: {title}", "", "Inbox"), "3. Inbox");
+        assert_eq!(label("{index}. {app}: {title}", "", ""), "3");
+    }
+
+    #[test]
+    fn truncation_boundaries() {
+        let text = "Long document — Chrome";
+        assert_eq!(truncate_chars(text, 22, TruncateMode::Middle), text);
+        assert_eq!(truncate_chars(text, 21, TruncateMode::Middle), "Long docum…t — Chrome");
+        assert_eq!(truncate_chars(text, 12, TruncateMode::Middle), "Long d…hrome");
+        assert_eq!(truncate_chars(text, 12, TruncateMode::End), "Long docume…");
+        assert_eq!(truncate_chars(text, 1, TruncateMode::Middle), "…");
+        assert_eq!(truncate_chars(text, 0, TruncateMode::End), "");
+        assert_eq!(truncate_chars("", 0, TruncateMode::Middle), "");
+    }
+
+    #[test]
+    fn format_applies_label_limit() {
+        let config = LabelConfig { truncate: TruncateMode::Middle, max_label_chars: Some(12), ..LabelConfig::default() };
+        let fields = LabelFields { app_name: "chrome", title: "Long document", index: 1, monitor: 1 };
+        assert_eq!(config.format(&fields), "Chrome…ument");
+    }
+
+    #[test]
+    fn unlimited_label_is_left_whole_at_the_end() {
+        let title = "x".repeat(200);
+        let fields = LabelFields { app_name: "", title: &title, index: 1, monitor: 1 };
+        assert_eq!(LabelConfig::default().format(&fields), title);
+        let zero = LabelConfig { max_label_chars: Some(0), ..LabelConfig::default() };
+        assert_eq!(zero.format(&fields), title);
+        let middle = LabelConfig { truncate: TruncateMode::Middle, ..LabelConfig::default() };
+        assert_eq!(middle.format(&fields).chars().count(), DEFAULT_MIDDLE_LABEL_CHARS);
+    }
+
+    #[test]
+    fn unknown_placeholders_are_left_literal() {
+        assert_eq!(label("{app} {host}", "firefox", ""), "Firefox {host}");
+        let mut config = LabelConfig { template: Some("{nope}".to_string()), ..LabelConfig::default() };
+        config.validate();
+        assert!(config.template.is_some());
+    }
+}
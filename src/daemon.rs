@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 
 use qol_plugin_api::daemon::{self as core_daemon, DaemonConfig, ReadResult};
@@ -8,12 +9,47 @@ const CONFIG: DaemonConfig = DaemonConfig {
     support_replace_existing: false,
 };
 
+/// Pings a silent socket gets before it counts as dead, on top of the core timeout.
+#[cfg(unix)]
+const STALE_PING_ATTEMPTS: u32 = 3;
+
 pub enum Command {
     Show,
     ShowReverse,
+    /// Open the picker showing only the focused app's windows (`--show-app`).
+    ShowAppOnly,
+    /// Open the picker showing only the windows of this smart group.
+    ShowGroup(String),
+    /// Activate this window without showing the picker (`--activate <id>`).
+    Activate(u32),
+    /// Activate the most recently used window on the active monitor.
+    SwitchRecentOnMonitor,
+    /// Switch the active config profile (`None` = base config).
+    SetProfile(Option<String>),
+    /// Write a JSON dump into this reply file reserved by the CLI.
+    Dump(DumpKind, PathBuf),
     Kill,
 }
 
+/// Data a `Command::Dump` asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpKind {
+    /// Focus-recency list (`--mru`).
+    Mru,
+    /// Current window list (`--list`).
+    Windows,
+}
+
+impl DumpKind {
+    /// Socket verb, also used to name the reply file.
+    pub fn verb(self) -> &'static str {
+        match self {
+            DumpKind::Mru => "mru",
+            DumpKind::Windows => "list",
+        }
+    }
+}
+
 pub fn send_show() -> bool {
     core_daemon::send_action(&CONFIG, "show", false)
 }
@@ -22,24 +58,146 @@ pub fn send_show_reverse() -> bool {
     core_daemon::send_action(&CONFIG, "show-reverse", false)
 }
 
+pub fn send_show_app() -> bool {
+    core_daemon::send_action(&CONFIG, "show-app", false)
+}
+
+pub fn send_group(name: &str) -> bool {
+    core_daemon::send_action(&CONFIG, &format!("group {}", name), false)
+}
+
+pub fn send_activate(window_id: u32) -> bool {
+    core_daemon::send_action(&CONFIG, &format!("activate {}", window_id), false)
+}
+
+pub fn send_switch_recent_on_monitor() -> bool {
+    core_daemon::send_action(&CONFIG, "switch-recent-on-monitor", false)
+}
+
+pub fn send_profile(name: &str) -> bool {
+    core_daemon::send_action(&CONFIG, &format!("profile {}", name), false)
+}
+
+pub fn send_dump(kind: DumpKind, path: &std::path::Path) -> bool {
+    core_daemon::send_action(&CONFIG, &format!("{} {}", kind.verb(), path.display()), false)
+}
+
 pub fn send_kill() -> bool {
     core_daemon::send_kill(&CONFIG)
 }
 
+/// True if a running daemon answers on the socket.
+pub fn ping() -> bool {
+    core_daemon::send_action(&CONFIG, "ping", false)
+}
+
 pub fn start_listener(tx: Sender<Command>) -> bool {
-    core_daemon::start_listener(&CONFIG, tx, parse_command)
+    if socket_is_stale() {
+        eprintln!("[alt-tab] removing stale daemon socket {}", socket_path().display());
+        let _ = std::fs::remove_file(socket_path());
+        let _ = std::fs::remove_file(pid_path());
+    }
+    let listening = core_daemon::start_listener(&CONFIG, tx, parse_command);
+    if listening {
+        let _ = std::fs::write(pid_path(), std::process::id().to_string());
+    }
+    listening
 }
 
 pub fn cleanup() {
     core_daemon::cleanup(&CONFIG);
+    let _ = std::fs::remove_file(pid_path());
+}
+
+/// Same location the core daemon binds (`use_tmpdir_env` honours `$TMPDIR`).
+fn socket_path() -> PathBuf {
+    std::env::temp_dir().join(CONFIG.default_socket_name)
 }
 
+/// PID of the daemon that owns the socket, written next to it.
+fn pid_path() -> PathBuf {
+    socket_path().with_extension("pid")
+}
+
+/// A socket left behind by a crashed daemon. On macOS such a socket doesn't
+/// always make the bind fail with `AddrInUse`, so check before binding:
+/// refused connections are stale; a socket that accepts but never answers a
+/// ping is stale only if the recorded daemon PID is gone too.
+#[cfg(unix)]
+fn socket_is_stale() -> bool {
+    use std::io::ErrorKind;
+    use std::os::unix::net::UnixStream;
+
+    let path = socket_path();
+    if !path.exists() {
+        return false;
+    }
+    match UnixStream::connect(&path) {
+        Err(e) => matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::NotFound),
+        Ok(_) => {
+            if (0..STALE_PING_ATTEMPTS).any(|_| ping()) {
+                return false;
+            }
+            !recorded_pid().is_some_and(pid_alive)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn socket_is_stale() -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn recorded_pid() -> Option<u32> {
+    std::fs::read_to_string(pid_path()).ok()?.trim().parse().ok()
+}
+
+/// `kill -0` succeeds only for a live process we may signal.
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+
 fn parse_command(cmd: &str) -> ReadResult<Command> {
     match cmd {
         "ping" => ReadResult::Handled,
         "show" | "open" => ReadResult::Command(Command::Show),
         "show-reverse" | "open-reverse" => ReadResult::Command(Command::ShowReverse),
+        "switch-recent-on-monitor" => ReadResult::Command(Command::SwitchRecentOnMonitor),
+        "show-app" => ReadResult::Command(Command::ShowAppOnly),
         "kill" => ReadResult::Command(Command::Kill),
-        _ => ReadResult::Fallback,
+        _ => {
+            for kind in [DumpKind::Mru, DumpKind::Windows] {
+                let Some(path) = cmd.strip_prefix(kind.verb()).and_then(|rest| rest.strip_prefix(' ')) else {
+                    continue;
+                };
+                let path = PathBuf::from(path.trim());
+                if !crate::dump::is_reserved(&path) {
+                    eprintln!("[alt-tab] ignoring {} request for {}", kind.verb(), path.display());
+                    return ReadResult::Handled;
+                }
+                return ReadResult::Command(Command::Dump(kind, path));
+            }
+            if let Some(Ok(id)) = cmd.strip_prefix("activate ").map(|id| id.trim().parse()) {
+                return ReadResult::Command(Command::Activate(id));
+            }
+            if let Some(name) = cmd.strip_prefix("group ").filter(|n| !n.trim().is_empty()) {
+                return ReadResult::Command(Command::ShowGroup(name.trim().to_string()));
+            }
+            match cmd.strip_prefix("profile ") {
+                Some(name) if !name.trim().is_empty() => {
+                    ReadResult::Command(Command::SetProfile(Some(name.trim().to_string())))
+                }
+                Some(_) => ReadResult::Command(Command::SetProfile(None)),
+                None => ReadResult::Fallback,
+            }
+        }
     }
 }
@@ -1,23 +1,67 @@
 use super::WindowDelegate;
 use crate::app::PICKER_VISIBLE;
-use crate::platform;
+use crate::platform::{self, Activation};
 use gpui::Window;
 use std::sync::atomic::Ordering;
 
 impl WindowDelegate {
-    pub(crate) fn activate_selected(&self, window: &mut Window) {
-        let Some(ix) = self.selected_index else {
-            return;
+    /// Returns false when the switch was refused and the picker left open.
+    pub(crate) fn activate_selected(&self, window: &mut Window) -> bool {
+        let Some(selected) = self.selected_index else {
+            return true;
         };
+        // If the selected window vanished (e.g. its app was just quit), fall
+        // through to the next window that can still be activated.
+        let total = self.windows.len();
+        let mut chosen = None;
+        let mut moved_to = None;
+        for i in (0..total).map(|offset| (selected + offset) % total) {
+            let id = self.windows[i].id;
+            // Move before raising, so the window never shows on the old monitor first.
+            let moved = self
+                .activation_monitor
+                .filter(|&monitor| platform::move_window_to_monitor(id, monitor));
+            match platform::activate_window(id, self.minimized_activate) {
+                Activation::Activated => {
+                    chosen = Some(i);
+                    moved_to = moved;
+                    break;
+                }
+                Activation::Gone => {}
+                Activation::Refused => {
+                    eprintln!("[alt-tab] switching to window {} was refused — keeping the picker open", id);
+                    return false;
+                }
+            }
+        }
+        let Some(ix) = chosen else {
+            eprintln!("[alt-tab] no activatable window left — dismissing");
+            PICKER_VISIBLE.store(false, Ordering::Relaxed);
+            platform::dismiss_picker(window);
+            return true;
+        };
+        if ix != selected {
+            eprintln!(
+                "[alt-tab] window {} is gone — activated {} instead",
+                self.windows[selected].id, self.windows[ix].id
+            );
+        }
         let win = &self.windows[ix];
-        platform::activate_window(win.id);
+        crate::focus_history::note_activated(win.id);
+        crate::events::emit(crate::events::AltTabEvent::Activate {
+            window_id: win.id,
+            app: win.app_name.clone(),
+            title: win.title.clone(),
+        });
 
         // Push the activated window's monitor to the runtime so the focus
         // stamp survives the AX "no focused application" gap.
         let client = qol_plugin_api::PlatformStateClient::from_env();
         if let Some(state) = client.get_state() {
-            let win_cx = win.x + win.width / 2.0;
-            let win_cy = win.y + win.height / 2.0;
+            let (win_cx, win_cy) = match moved_to {
+                Some((mx, my, mw, mh)) => (mx + mw / 2.0, my + mh / 2.0),
+                None => (win.x + win.width / 2.0, win.y + win.height / 2.0),
+            };
             let idx = state
                 .monitors
                 .iter()
@@ -46,5 +90,6 @@ impl WindowDelegate {
 
         PICKER_VISIBLE.store(false, Ordering::Relaxed);
         platform::dismiss_picker(window);
+        true
     }
 }
@@ -0,0 +1,65 @@
+use super::WindowDelegate;
+use crate::platform::WindowInfo;
+use std::collections::HashSet;
+
+impl WindowDelegate {
+    /// The cards to show for `all_windows`: everything, one card per app
+    /// (its frontmost window), or just the expanded app's windows.
+    pub(super) fn displayed_windows(&mut self) -> Vec<WindowInfo> {
+        self.app_window_counts.clear();
+        if !self.collapse_apps {
+            return self.all_windows.clone();
+        }
+        for w in &self.all_windows {
+            *self.app_window_counts.entry(w.app_name.clone()).or_insert(0) += 1;
+        }
+        if let Some(app) = &self.expanded_app {
+            if self.app_window_counts.contains_key(app) {
+                return self
+                    .all_windows
+                    .iter()
+                    .filter(|w| &w.app_name == app)
+                    .cloned()
+                    .collect();
+            }
+            self.expanded_app = None;
+        }
+        let mut seen = HashSet::new();
+        self.all_windows
+            .iter()
+            .filter(|w| seen.insert(w.app_name.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Collapsed mode: show the selected app's windows individually, or go
+    /// back to one card per app. Returns false when nothing changed.
+    pub(crate) fn toggle_expand_app(&mut self) -> bool {
+        if !self.collapse_apps {
+            return false;
+        }
+        let selected_app = self
+            .selected_index
+            .and_then(|ix| self.windows.get(ix))
+            .map(|w| w.app_name.clone());
+        match self.expanded_app.take() {
+            Some(app) => {
+                self.windows = self.displayed_windows();
+                self.selected_index = self
+                    .windows
+                    .iter()
+                    .position(|w| w.app_name == app)
+                    .or(if self.windows.is_empty() { None } else { Some(0) });
+            }
+            None => {
+                let Some(app) = selected_app else {
+                    return false;
+                };
+                self.expanded_app = Some(app);
+                self.windows = self.displayed_windows();
+                self.selected_index = if self.windows.is_empty() { None } else { Some(0) };
+            }
+        }
+        true
+    }
+}
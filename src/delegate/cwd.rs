@@ -0,0 +1,62 @@
+use regex::Regex;
+
+/// Working directory shown in `title`. With `pattern`, its first capture group
+/// (or the whole match); otherwise the first word that looks like a path.
+pub(crate) fn cwd_from_title(title: &str, pattern: Option<&Regex>) -> Option<String> {
+    if let Some(re) = pattern {
+        let caps = re.captures(title)?;
+        let found = caps.get(1).or_else(|| caps.get(0))?.as_str().trim();
+        return (!found.is_empty()).then(|| found.to_string());
+    }
+    title
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '—' || c == '|')
+        .map(|word| word.trim_matches(|c: char| matches!(c, '(' | ')' | '[' | ']' | '"' | '\'')))
+        .find(|word| word.starts_with('/') || word == &"~" || word.starts_with("~/"))
+        .map(str::to_string)
+}
+
+/// Short badge text for `cwd`: its last path component.
+pub(crate) fn project_name(cwd: &str) -> &str {
+    let trimmed = cwd.trim_end_matches('/');
+    match trimmed.rsplit('/').next() {
+        Some(last) if !last.is_empty() => last,
+        _ => cwd,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_paths_in_common_terminal_titles() {
+        assert_eq!(cwd_from_title("me@host: ~/src/app", None).as_deref(), Some("~/src/app"));
+        assert_eq!(cwd_from_title("zsh — ~/src/app — 80×24", None).as_deref(), Some("~/src/app"));
+        assert_eq!(cwd_from_title("nvim (/etc/nginx)", None).as_deref(), Some("/etc/nginx"));
+        assert_eq!(cwd_from_title("me@host: ~", None).as_deref(), Some("~"));
+    }
+
+    #[test]
+    fn titles_without_a_path_have_no_cwd() {
+        assert_eq!(cwd_from_title("main.rs — plugin-alt-tab", None), None);
+        assert_eq!(cwd_from_title("", None), None);
+    }
+
+    #[test]
+    fn pattern_uses_first_capture_group() {
+        let re = Regex::new(r"— (\S+) — Visual Studio Code$").unwrap();
+        assert_eq!(
+            cwd_from_title("main.rs — plugin-alt-tab — Visual Studio Code", Some(&re)).as_deref(),
+            Some("plugin-alt-tab")
+        );
+        assert_eq!(cwd_from_title("Welcome", Some(&re)), None);
+    }
+
+    #[test]
+    fn project_name_is_last_component() {
+        assert_eq!(project_name("~/src/app"), "app");
+        assert_eq!(project_name("/srv/site/"), "site");
+        assert_eq!(project_name("~"), "~");
+        assert_eq!(project_name("/"), "/");
+    }
+}
@@ -1,58 +1,327 @@
 mod activation;
+mod collapse;
+pub(crate) mod cwd;
+pub(crate) mod ordering;
 mod selection;
+pub(crate) mod shortcuts;
 
 
-use crate::config::LabelConfig;
+use crate::config::{
+    AltTabConfig, DisplayConfig, FilterSpec, LabelConfig, MinimizedActivate, MinimizedSort,
+    PreviewQuality, QuickSelect, WindowOrder,
+};
+use crate::monitor::MonitorFilter;
 use crate::platform::WindowInfo;
-use gpui::RenderImage;
-use std::collections::HashMap;
+use crate::preview::TimedPreviews;
+use gpui::{Font, FontFallbacks, RenderImage};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Selection border color when no system accent is used.
+const DEFAULT_ACCENT: u32 = 0x4a6fa5;
+
+/// How long a preview outlives its window's absence from enumeration, so a
+/// window that drops out for one refresh doesn't flicker to a placeholder.
+const PREVIEW_EVICTION_GRACE: Duration = Duration::from_secs(3);
+
+/// Upper bound for `hover_scale`; beyond this a card covers its neighbours.
+const MAX_HOVER_SCALE: f32 = 1.3;
+
+/// Consecutive failed captures before a window counts as previewless.
+const MAX_CAPTURE_FAILURES: u32 = 3;
+/// With `indicate_stale_previews`, a preview not recaptured for this long is tinted.
+const STALE_PREVIEW_AGE: Duration = Duration::from_secs(5);
 
 pub(crate) struct WindowDelegate {
+    /// Displayed cards; equals `all_windows` unless `collapse_apps` is on.
     pub(crate) windows: Vec<WindowInfo>,
+    /// Every enumerated window, in z-order.
+    pub(crate) all_windows: Vec<WindowInfo>,
+    pub(crate) collapse_apps: bool,
+    /// App whose windows are shown individually while collapsed.
+    pub(crate) expanded_app: Option<String>,
+    /// Windows per app name, for the collapsed card count.
+    pub(crate) app_window_counts: HashMap<String, usize>,
     pub(crate) selected_index: Option<usize>,
     pub(crate) label_config: LabelConfig,
     pub(crate) transparent_background: bool,
     pub(crate) card_bg_color: u32,
     pub(crate) card_bg_opacity: f32,
+    /// Configured UI font; `None` keeps GPUI's default.
+    pub(crate) font: Option<Font>,
+    pub(crate) font_scale: f32,
+    /// Selected-card border color (`0xRRGGBB`).
+    pub(crate) accent_color: u32,
+    /// Selected-card glow color and intensity.
+    pub(crate) selection_glow: Option<(u32, f32)>,
+    pub(crate) selection_glow_pulse: bool,
     pub(crate) show_debug_overlay: bool,
     pub(crate) show_hotkey_hints: bool,
+    pub(crate) show_action_bar: bool,
+    pub(crate) show_recent_strip: bool,
+    pub(crate) icon_placeholder: bool,
+    pub(crate) placeholder_image: Option<Arc<RenderImage>>,
+    pub(crate) indicate_stale_previews: bool,
+    /// `group_by_cwd_for_apps`: apps whose cards show a working-directory badge.
+    cwd_apps: Vec<String>,
+    cwd_pattern: Option<regex::Regex>,
+    pub(crate) show_shortcut_badges: bool,
+    pub(crate) quick_select: QuickSelect,
+    pub(crate) preview_quality: PreviewQuality,
+    pub(crate) capture_cursor: bool,
+    pub(crate) screen_capture_kit: bool,
+    pub(crate) large_window_capture_pixels: u64,
+    /// `display.preview_memory_mb` in bytes; None when unlimited.
+    pub(crate) preview_budget: Option<usize>,
+    pub(crate) preview_selected_only: bool,
+    /// `display` as last applied; answers the `always_preview_apps` allowlist.
+    pub(crate) display: DisplayConfig,
+    pub(crate) stream_selected_fps: u32,
+    pub(crate) animated_preview_interval_ms: u64,
+    pub(crate) show_app_badges: bool,
+    /// Badge text by app name, refreshed by the live preview loop.
+    pub(crate) app_badges: HashMap<String, String>,
+    pub(crate) show_app_window_count: bool,
+    /// Runtime copy of `display.show_minimized`; toggled with `m` while open.
+    pub(crate) show_minimized: bool,
+    pub(crate) include_other_spaces: bool,
+    pub(crate) include_desktop: bool,
+    pub(crate) cycle_wrap: bool,
+    pub(crate) vim_navigation: bool,
+    /// Cards flow right-to-left; left/right navigation is mirrored to match.
+    pub(crate) rtl: bool,
+    /// Hovered cards grow to this size (1.0 = off).
+    pub(crate) hover_scale: f32,
+    /// `display.card_scale`, clamped.
+    pub(crate) card_scale: f32,
+    pub(crate) minimized_sort: MinimizedSort,
+    pub(crate) minimized_activate: MinimizedActivate,
+    pub(crate) window_order: WindowOrder,
+    /// With `activate_to_active_monitor`: the monitor (x, y, w, h) the picker opened on.
+    pub(crate) activation_monitor: Option<(f32, f32, f32, f32)>,
+    /// With `exclude_current_window`: the window focused when the picker opened.
+    pub(crate) excluded_window: Option<u32>,
+    /// The window focused when the picker opened; its card gets a faint accent ring.
+    pub(crate) current_window_id: Option<u32>,
+    /// Smart group the picker was opened with (`--group`); refreshes keep to it.
+    pub(crate) group_filter: Option<FilterSpec>,
+    /// With `current_monitor_only`: keeps windows on the monitor the picker opened on.
+    pub(crate) monitor_filter: Option<MonitorFilter>,
+    pub(crate) hide_previewless_windows: bool,
+    capture_failures: HashMap<u32, u32>,
     pub(crate) live_previews: HashMap<u32, Arc<RenderImage>>,
+    /// When each cached preview's window was first missing from enumeration.
+    preview_missing_since: HashMap<u32, Instant>,
+    /// When each cached preview was last captured (or received from the prewarm).
+    preview_captured_at: HashMap<u32, Instant>,
+    /// Stale previews at the last check, so a repaint happens when one ages out.
+    stale_preview_count: usize,
+    /// When each preview was last drawn, for dropping the least recently shown
+    /// ones past `preview_budget`.
+    preview_shown_at: HashMap<u32, Instant>,
+    /// Cards inside the grid's viewport at the last render; their previews are
+    /// never dropped for the budget.
+    cards_on_screen: HashSet<u32>,
     pub(crate) icon_cache: HashMap<String, Arc<RenderImage>>,
 }
 
 impl WindowDelegate {
     pub(crate) fn new_with_previews(
         windows: Vec<WindowInfo>,
-        label_config: LabelConfig,
-        transparent_background: bool,
-        card_bg_color: u32,
-        card_bg_opacity: f32,
-        show_debug_overlay: bool,
-        show_hotkey_hints: bool,
-        live_previews: HashMap<u32, Arc<RenderImage>>,
+        config: &AltTabConfig,
+        previews: TimedPreviews,
         icon_cache: HashMap<String, Arc<RenderImage>>,
     ) -> Self {
-        let selected_index = if windows.is_empty() { None } else { Some(0) };
-        Self {
-            windows,
-            selected_index,
-            label_config,
-            transparent_background,
-            card_bg_color,
-            card_bg_opacity,
-            show_debug_overlay,
-            show_hotkey_hints,
-            live_previews,
+        let mut delegate = Self {
+            windows: Vec::new(),
+            all_windows: Vec::new(),
+            collapse_apps: false,
+            expanded_app: None,
+            app_window_counts: HashMap::new(),
+            selected_index: None,
+            label_config: LabelConfig::default(),
+            transparent_background: false,
+            card_bg_color: 0,
+            card_bg_opacity: 1.0,
+            font: None,
+            font_scale: 1.0,
+            accent_color: DEFAULT_ACCENT,
+            selection_glow: None,
+            selection_glow_pulse: false,
+            show_debug_overlay: false,
+            show_hotkey_hints: true,
+            show_action_bar: false,
+            show_recent_strip: false,
+            icon_placeholder: true,
+            placeholder_image: None,
+            indicate_stale_previews: false,
+            cwd_apps: Vec::new(),
+            cwd_pattern: None,
+            show_shortcut_badges: false,
+            quick_select: QuickSelect::default(),
+            preview_quality: PreviewQuality::default(),
+            capture_cursor: false,
+            screen_capture_kit: false,
+            large_window_capture_pixels: 0,
+            preview_budget: None,
+            preview_selected_only: false,
+            display: DisplayConfig::default(),
+            stream_selected_fps: 0,
+            animated_preview_interval_ms: 0,
+            show_app_badges: false,
+            app_badges: HashMap::new(),
+            show_app_window_count: false,
+            show_minimized: true,
+            include_other_spaces: false,
+            include_desktop: false,
+            cycle_wrap: true,
+            vim_navigation: false,
+            rtl: false,
+            hover_scale: 1.0,
+            card_scale: 1.0,
+            minimized_sort: MinimizedSort::default(),
+            minimized_activate: MinimizedActivate::default(),
+            window_order: WindowOrder::default(),
+            activation_monitor: None,
+            excluded_window: None,
+            current_window_id: None,
+            group_filter: None,
+            monitor_filter: None,
+            hide_previewless_windows: false,
+            capture_failures: HashMap::new(),
+            live_previews: HashMap::new(),
+            preview_missing_since: HashMap::new(),
+            preview_captured_at: HashMap::new(),
+            stale_preview_count: 0,
+            preview_shown_at: HashMap::new(),
+            cards_on_screen: HashSet::new(),
             icon_cache,
+        };
+        delegate.apply_config(config);
+        delegate.insert_previews(previews);
+        delegate.set_windows(windows, true);
+        delegate
+    }
+
+    /// Copy config-derived settings onto the delegate (fresh open and reuse paths).
+    pub(crate) fn apply_config(&mut self, config: &AltTabConfig) {
+        let (card_color, card_opacity) = config.display.card_bg();
+        self.label_config = config.label.clone();
+        self.transparent_background = config.display.transparent_background;
+        self.card_bg_color = card_color;
+        self.card_bg_opacity = card_opacity;
+        self.font = config.display.font_family.as_ref().map(|family| {
+            let mut font = gpui::font(family.clone());
+            if !config.display.font_fallbacks.is_empty() {
+                font.fallbacks = Some(FontFallbacks::from_fonts(config.display.font_fallbacks.clone()));
+            }
+            font
+        });
+        self.font_scale = config.display.font_scale.clamp(0.75, 1.5);
+        // Queried once per open; the accent rarely changes while the picker is up.
+        let system_accent = config
+            .display
+            .use_system_accent
+            .then(crate::platform::system_accent_color)
+            .flatten();
+        self.accent_color = system_accent.unwrap_or(DEFAULT_ACCENT);
+        self.selection_glow = config
+            .display
+            .selection_glow()
+            .map(|(color, intensity)| (system_accent.unwrap_or(color), intensity));
+        self.selection_glow_pulse = self.selection_glow.is_some()
+            && config.display.selection_glow_pulse
+            && !crate::platform::prefers_reduced_motion();
+        self.show_debug_overlay = config.display.show_debug_overlay;
+        self.show_hotkey_hints = config.display.show_hotkey_hints;
+        self.show_action_bar = config.display.show_action_bar;
+        self.show_recent_strip = config.display.show_recent_strip;
+        self.icon_placeholder = config.display.icon_placeholder;
+        self.placeholder_image = crate::preview::placeholder_image(config.display.placeholder_image_path());
+        self.indicate_stale_previews = config.display.indicate_stale_previews;
+        self.cwd_apps = config.group_by_cwd_for_apps.clone();
+        self.cwd_pattern = config.cwd_title_pattern.as_deref().and_then(|pattern| {
+            regex::Regex::new(pattern)
+                .map_err(|e| eprintln!("[alt-tab] invalid cwd_title_pattern: {} — using the path heuristic", e))
+                .ok()
+        });
+        self.show_shortcut_badges = config.display.show_shortcut_badges;
+        self.quick_select = config.quick_select;
+        self.preview_quality = config.display.preview_quality;
+        self.capture_cursor = config.display.capture_cursor;
+        self.screen_capture_kit = config.display.screen_capture_kit;
+        self.large_window_capture_pixels = config.display.large_window_capture_pixels;
+        self.preview_budget = config.display.preview_memory_budget();
+        self.preview_selected_only = config.display.preview_selected_only;
+        self.display = config.display.clone();
+        self.stream_selected_fps = config.display.stream_selected_fps;
+        self.animated_preview_interval_ms = config.display.animated_preview_interval_ms;
+        self.show_app_badges = config.display.show_app_badges;
+        if !self.show_app_badges {
+            self.app_badges.clear();
+        }
+        self.show_app_window_count = config.display.show_app_window_count;
+        self.show_minimized = config.display.show_minimized;
+        self.include_other_spaces = config.display.include_other_spaces;
+        self.include_desktop = config.display.include_desktop;
+        self.cycle_wrap = config.cycle_wrap;
+        self.vim_navigation = config.vim_navigation;
+        self.rtl = config.display.direction.is_rtl();
+        self.hover_scale = config.display.hover_scale.clamp(1.0, MAX_HOVER_SCALE);
+        self.card_scale = config.display.card_scale();
+        self.minimized_sort = config.minimized_sort;
+        self.minimized_activate = config.minimized_activate;
+        self.window_order = config.display.order;
+        self.hide_previewless_windows = config.display.hide_previewless_windows;
+        self.collapse_apps = config.collapse_apps;
+        self.expanded_app = None;
+    }
+
+    /// Whether `win` gets a live preview: always, unless `preview_selected_only`
+    /// limits previews to the selection and its app isn't allowlisted.
+    pub(crate) fn wants_preview(&self, win: &WindowInfo) -> bool {
+        !self.preview_selected_only
+            || self.selected_window_id() == Some(win.id)
+            || self.display.always_previews(&win.app_name)
+    }
+
+    pub(crate) fn selected_window_id(&self) -> Option<u32> {
+        self.selected_index
+            .and_then(|ix| self.windows.get(ix))
+            .map(|w| w.id)
+    }
+
+    /// Select the window with `window_id` if it is still listed.
+    pub(crate) fn select_window_id(&mut self, window_id: u32) {
+        if let Some(ix) = self.windows.iter().position(|w| w.id == window_id) {
+            self.selected_index = Some(ix);
+        }
+    }
+
+    /// Badge exactly the windows in `unsaved` as having unsaved changes.
+    pub(crate) fn mark_unsaved(&mut self, unsaved: &HashSet<u32>) {
+        for w in self.all_windows.iter_mut().chain(self.windows.iter_mut()) {
+            w.has_unsaved_changes = unsaved.contains(&w.id);
         }
     }
 
+    /// Replace the window list. Without `reset_selection` the selected window
+    /// stays selected wherever it moved; if it is gone, the index is clamped.
     pub(crate) fn set_windows(&mut self, windows: Vec<WindowInfo>, reset_selection: bool) {
-        self.windows = windows;
-        let active_ids: std::collections::HashSet<u32> =
-            self.windows.iter().map(|w| w.id).collect();
-        self.live_previews.retain(|id, _| active_ids.contains(id));
+        let previously_selected = self.selected_window_id();
+        self.all_windows = ordering::arrange(
+            windows,
+            self.window_order,
+            self.minimized_sort,
+            &crate::focus_history::snapshot(),
+        );
+        self.windows = self.displayed_windows();
+        let active_ids: HashSet<u32> =
+            self.all_windows.iter().map(|w| w.id).collect();
+        self.evict_stale_previews(&active_ids);
+        self.capture_failures.retain(|id, _| active_ids.contains(id));
+        self.drop_previewless();
         if self.windows.is_empty() {
             self.selected_index = None;
             #[cfg(debug_assertions)]
@@ -74,8 +343,10 @@ impl WindowDelegate {
             return;
         }
 
+        let kept = previously_selected
+            .and_then(|id| self.windows.iter().position(|w| w.id == id));
         let selected_row = self.selected_index.unwrap_or(0);
-        self.selected_index = Some(selected_row.min(self.windows.len() - 1));
+        self.selected_index = Some(kept.unwrap_or(selected_row.min(self.windows.len() - 1)));
         #[cfg(debug_assertions)]
         eprintln!(
             "[alt-tab/select] set_windows reset={} next={:?} total={}",
@@ -85,23 +356,213 @@ impl WindowDelegate {
         );
     }
 
+    /// Drop previews whose window has been missing for longer than the grace period.
+    fn evict_stale_previews(&mut self, active_ids: &HashSet<u32>) {
+        let now = Instant::now();
+        let missing_since = &mut self.preview_missing_since;
+        missing_since.retain(|id, _| !active_ids.contains(id));
+        self.live_previews.retain(|id, _| {
+            if active_ids.contains(id) {
+                return true;
+            }
+            let since = *missing_since.entry(*id).or_insert(now);
+            now.duration_since(since) < PREVIEW_EVICTION_GRACE
+        });
+        let live_previews = &self.live_previews;
+        missing_since.retain(|id, _| live_previews.contains_key(id));
+        self.preview_captured_at.retain(|id, _| live_previews.contains_key(id));
+        self.preview_shown_at.retain(|id, _| live_previews.contains_key(id));
+        self.enforce_preview_budget();
+    }
+
+    /// Record which cards the render painted inside the viewport and stamp
+    /// their previews as shown.
+    pub(crate) fn note_previews_shown(&mut self, on_screen: impl IntoIterator<Item = u32>) {
+        let now = Instant::now();
+        self.cards_on_screen = on_screen.into_iter().collect();
+        for id in &self.cards_on_screen {
+            if self.live_previews.contains_key(id) {
+                self.preview_shown_at.insert(*id, now);
+            }
+        }
+    }
+
+    /// Whether `id`'s card was inside the viewport at the last render.
+    pub(crate) fn card_on_screen(&self, id: u32) -> bool {
+        self.cards_on_screen.contains(&id)
+    }
+
+    /// With `preview_budget`, drop the least recently shown previews until the
+    /// rest fit, spilling them to disk. Previews of on-screen cards and the
+    /// selection are never dropped, even if they alone exceed the budget.
+    pub(crate) fn enforce_preview_budget(&mut self) {
+        let Some(budget) = self.preview_budget else {
+            return;
+        };
+        let selected = self.selected_window_id();
+        let protected = |id: u32| Some(id) == selected || self.cards_on_screen.contains(&id);
+        // Listed windows rank by position when their shown stamps tie.
+        let position: HashMap<u32, usize> =
+            self.windows.iter().enumerate().map(|(i, w)| (w.id, i)).collect();
+        let mut entries: Vec<(u32, usize)> = self
+            .live_previews
+            .iter()
+            .map(|(id, img)| (*id, crate::preview::render_image_bytes(img)))
+            .collect();
+        entries.sort_by_key(|(id, _)| {
+            (
+                !protected(*id),
+                std::cmp::Reverse(self.preview_shown_at.get(id).copied()),
+                position.get(id).copied().unwrap_or(usize::MAX),
+            )
+        });
+        let dropped: Vec<u32> = crate::preview::over_budget(&entries, budget)
+            .into_iter()
+            .filter(|id| !protected(*id))
+            .collect();
+        let mut spilled = Vec::with_capacity(dropped.len());
+        for id in dropped {
+            if let Some(image) = self.live_previews.remove(&id) {
+                spilled.push((id, image));
+            }
+            self.preview_captured_at.remove(&id);
+            self.preview_shown_at.remove(&id);
+        }
+        crate::preview::spill(spilled);
+    }
+
+    /// Add previews captured earlier, keeping their capture times.
+    pub(crate) fn insert_previews(&mut self, previews: TimedPreviews) {
+        for (id, (image, captured_at)) in previews {
+            self.live_previews.insert(id, image);
+            self.preview_captured_at.insert(id, captured_at);
+        }
+    }
+
+    /// Mark the previews of `ids` as freshly captured.
+    pub(crate) fn note_previews_captured(&mut self, ids: impl IntoIterator<Item = u32>) {
+        let now = Instant::now();
+        for id in ids {
+            self.preview_captured_at.insert(id, now);
+        }
+    }
+
+    /// With `indicate_stale_previews`: the cached preview of `id` has not been
+    /// recaptured within `STALE_PREVIEW_AGE`.
+    pub(crate) fn preview_is_stale(&self, id: u32) -> bool {
+        self.indicate_stale_previews
+            && self.live_previews.contains_key(&id)
+            && self
+                .preview_captured_at
+                .get(&id)
+                .map_or(true, |t| t.elapsed() >= STALE_PREVIEW_AGE)
+    }
+
+    /// True when the number of stale previews changed since the last call.
+    pub(crate) fn stale_previews_changed(&mut self) -> bool {
+        if !self.indicate_stale_previews {
+            return false;
+        }
+        let count = self.windows.iter().filter(|w| self.preview_is_stale(w.id)).count();
+        std::mem::replace(&mut self.stale_preview_count, count) != count
+    }
+
+    /// Count capture failures per window; a success resets the count.
+    /// A round where nothing captured (no permission, unsupported platform) is
+    /// ignored so a global failure never hides everything.
+    /// Returns true if a window was dropped as previewless.
+    pub(crate) fn record_capture_results(&mut self, failed: &[u32], succeeded: &[u32]) -> bool {
+        if succeeded.is_empty() {
+            return false;
+        }
+        self.note_previews_captured(succeeded.iter().copied());
+        for id in succeeded {
+            self.capture_failures.remove(id);
+        }
+        for id in failed {
+            *self.capture_failures.entry(*id).or_insert(0) += 1;
+        }
+        let dropped = self.drop_previewless();
+        if dropped {
+            let selected = self.selected_index.unwrap_or(0);
+            self.selected_index = Some(selected.min(self.windows.len().saturating_sub(1)));
+        }
+        dropped
+    }
+
+    /// Capture of `id` failed `MAX_CAPTURE_FAILURES` times in a row and it has no
+    /// cached preview, so its card shows the app icon as the preview.
+    pub(crate) fn capture_gave_up(&self, id: u32) -> bool {
+        !self.live_previews.contains_key(&id)
+            && self.capture_failures.get(&id).copied().unwrap_or(0) >= MAX_CAPTURE_FAILURES
+    }
+
+    /// With `hide_previewless_windows`, remove windows that exhausted their capture
+    /// retries and have no cached preview. The frontmost (focused) and selected
+    /// windows are always kept.
+    fn drop_previewless(&mut self) -> bool {
+        if !self.hide_previewless_windows {
+            return false;
+        }
+        let frontmost = self.current_window_id;
+        let selected = self.selected_window_id();
+        let (failures, previews) = (&self.capture_failures, &self.live_previews);
+        let dropped: HashSet<u32> = self
+            .windows
+            .iter()
+            .filter(|w| {
+                Some(w.id) != frontmost
+                    && Some(w.id) != selected
+                    && !previews.contains_key(&w.id)
+                    && failures.get(&w.id).copied().unwrap_or(0) >= MAX_CAPTURE_FAILURES
+            })
+            .map(|w| w.id)
+            .collect();
+        if dropped.is_empty() {
+            return false;
+        }
+        self.windows.retain(|w| !dropped.contains(&w.id));
+        self.all_windows.retain(|w| !dropped.contains(&w.id));
+        if let Some(id) = selected {
+            self.select_window_id(id);
+        }
+        true
+    }
+
+    /// Project badge for `win` when its app is in `group_by_cwd_for_apps`.
+    pub(crate) fn cwd_badge(&self, win: &WindowInfo) -> Option<String> {
+        if !self.cwd_apps.iter().any(|app| app.eq_ignore_ascii_case(&win.app_name)) {
+            return None;
+        }
+        let cwd = cwd::cwd_from_title(&win.title, self.cwd_pattern.as_ref())?;
+        Some(cwd::project_name(&cwd).to_string())
+    }
+
+    /// Merge freshly fetched icons, evicting only apps that no longer have windows.
+    /// A partial fetch therefore never drops icons that are still in use.
+    pub(crate) fn merge_icons(&mut self, icons: HashMap<String, Arc<RenderImage>>) {
+        self.icon_cache.extend(icons);
+        let live_apps: HashSet<&str> = self.all_windows.iter().map(|w| w.app_name.as_str()).collect();
+        self.icon_cache.retain(|name, _| live_apps.contains(name.as_str()));
+    }
+
     /// Optimistically remove a single window (e.g. after close).
     pub(crate) fn remove_window(&mut self, window_id: u32) {
-        let remaining: Vec<_> = self.windows.iter().filter(|w| w.id != window_id).cloned().collect();
+        let remaining: Vec<_> = self.all_windows.iter().filter(|w| w.id != window_id).cloned().collect();
         self.set_windows(remaining, false);
     }
 
     /// Optimistically remove all windows belonging to an app (e.g. after quit).
     pub(crate) fn remove_app_windows(&mut self, app_name: &str) {
-        let remaining: Vec<_> = self.windows.iter().filter(|w| w.app_name != app_name).cloned().collect();
+        let remaining: Vec<_> = self.all_windows.iter().filter(|w| w.app_name != app_name).cloned().collect();
         self.set_windows(remaining, false);
     }
 
     /// Optimistically mark a window as minimized and move it to the end.
     pub(crate) fn mark_minimized(&mut self, window_id: u32) {
-        let mut reordered = Vec::with_capacity(self.windows.len());
+        let mut reordered = Vec::with_capacity(self.all_windows.len());
         let mut target = None;
-        for w in self.windows.drain(..) {
+        for w in self.all_windows.drain(..) {
             if w.id == window_id {
                 target = Some(w);
             } else {
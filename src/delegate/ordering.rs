@@ -0,0 +1,170 @@
+use crate::config::{MinimizedSort, WindowOrder};
+use crate::platform::WindowInfo;
+use std::collections::HashMap;
+
+/// `windows` (in stacking order) arranged for display by `order`, with
+/// minimized windows placed per `minimized` where the order leaves it open.
+pub(crate) fn arrange(
+    windows: Vec<WindowInfo>,
+    order: WindowOrder,
+    minimized: MinimizedSort,
+    stamps: &HashMap<u32, u64>,
+) -> Vec<WindowInfo> {
+    match (order, minimized) {
+        // Recency already places minimized windows by their last focus.
+        (WindowOrder::Mru, _) => order_by_recency(windows, stamps),
+        // Sorting places minimized windows alphabetically along with the rest.
+        (WindowOrder::AlphabeticalByTitle, _) => order_by_title(windows),
+        (WindowOrder::AlphabeticalByApp, _) => order_by_app(windows),
+        (WindowOrder::ZOrder, MinimizedSort::Last) => windows,
+        (WindowOrder::ZOrder, MinimizedSort::ByMru) => interleave_minimized(windows, stamps),
+    }
+}
+
+/// Move each minimized window from the end of `windows` to where its last focus
+/// falls among the visible ones. Visible windows keep their stacking order; a
+/// minimized window goes after every visible window focused more recently than
+/// it. Windows without a focus stamp are treated as oldest.
+fn interleave_minimized(
+    windows: Vec<WindowInfo>,
+    stamps: &HashMap<u32, u64>,
+) -> Vec<WindowInfo> {
+    let stamp = |w: &WindowInfo| stamps.get(&w.id).copied();
+    let (mut minimized, visible): (Vec<_>, Vec<_>) =
+        windows.into_iter().partition(|w| w.is_minimized);
+    // Newest first; unstamped keep their relative order at the end.
+    minimized.sort_by(|a, b| stamp(b).cmp(&stamp(a)));
+
+    let mut result = Vec::with_capacity(visible.len() + minimized.len());
+    let mut pending = minimized.into_iter().peekable();
+    for w in visible {
+        while let Some(m) = pending.next_if(|m| stamp(m).is_some() && stamp(m) > stamp(&w)) {
+            result.push(m);
+        }
+        result.push(w);
+    }
+    result.extend(pending);
+    result
+}
+
+/// Most recently focused first. Windows without a focus stamp follow every
+/// stamped one, keeping their stacking order.
+fn order_by_recency(mut windows: Vec<WindowInfo>, stamps: &HashMap<u32, u64>) -> Vec<WindowInfo> {
+    // Stable sort: Reverse(None) sorts after every Reverse(Some(_)).
+    windows.sort_by_key(|w| std::cmp::Reverse(stamps.get(&w.id).copied()));
+    windows
+}
+
+/// Case-insensitive by title. Stable, so equal titles keep their stacking order.
+fn order_by_title(mut windows: Vec<WindowInfo>) -> Vec<WindowInfo> {
+    windows.sort_by_cached_key(|w| w.title.to_lowercase());
+    windows
+}
+
+/// Case-insensitive by app name, then title, so each app's windows stay grouped.
+fn order_by_app(mut windows: Vec<WindowInfo>) -> Vec<WindowInfo> {
+    windows.sort_by_cached_key(|w| (w.app_name.to_lowercase(), w.title.to_lowercase()));
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(id: u32, is_minimized: bool) -> WindowInfo {
+        WindowInfo {
+            id,
+            title: format!("window {id}"),
+            app_name: "app".to_string(),
+            preview_path: None,
+            icon: None,
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+            is_minimized,
+            is_off_space: false,
+            has_unsaved_changes: false,
+        }
+    }
+
+    fn named(id: u32, app_name: &str, title: &str) -> WindowInfo {
+        WindowInfo { app_name: app_name.to_string(), title: title.to_string(), ..win(id, false) }
+    }
+
+    fn ids(windows: &[WindowInfo]) -> Vec<u32> {
+        windows.iter().map(|w| w.id).collect()
+    }
+
+    #[test]
+    fn minimized_window_sorts_between_visible_by_focus() {
+        let windows = vec![win(1, false), win(2, false), win(3, true)];
+        let stamps = HashMap::from([(1, 10), (2, 5), (3, 7)]);
+        assert_eq!(ids(&interleave_minimized(windows, &stamps)), vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn unstamped_minimized_windows_stay_last() {
+        let windows = vec![win(1, false), win(2, true), win(3, true)];
+        let stamps = HashMap::from([(1, 1)]);
+        assert_eq!(ids(&interleave_minimized(windows, &stamps)), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn visible_stacking_order_is_kept() {
+        let windows = vec![win(1, false), win(2, false), win(3, false)];
+        let stamps = HashMap::from([(3, 9), (1, 1)]);
+        assert_eq!(ids(&interleave_minimized(windows, &stamps)), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn recency_order_puts_last_focused_first() {
+        let windows = vec![win(1, false), win(2, false), win(3, false)];
+        let stamps = HashMap::from([(1, 4), (2, 9), (3, 6)]);
+        assert_eq!(ids(&order_by_recency(windows, &stamps)), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn unknown_windows_follow_known_in_stacking_order() {
+        let windows = vec![win(1, false), win(2, false), win(3, false), win(4, true)];
+        let stamps = HashMap::from([(3, 1)]);
+        assert_eq!(ids(&order_by_recency(windows, &stamps)), vec![3, 1, 2, 4]);
+    }
+
+    #[test]
+    fn title_order_ignores_case() {
+        let windows = vec![named(1, "a", "beta"), named(2, "a", "Alpha"), named(3, "a", "gamma"), named(4, "a", "Beta 2")];
+        assert_eq!(ids(&order_by_title(windows)), vec![2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn title_order_is_stable_for_equal_titles() {
+        let windows = vec![named(3, "a", "Terminal"), named(1, "b", "terminal"), named(2, "c", "TERMINAL")];
+        assert_eq!(ids(&order_by_title(windows)), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn app_order_groups_apps_and_sorts_titles_within() {
+        let windows = vec![
+            named(1, "kitty", "zsh"),
+            named(2, "Firefox", "Mail"),
+            named(3, "kitty", "htop"),
+            named(4, "firefox", "docs"),
+            named(5, "Code", "main.rs"),
+        ];
+        assert_eq!(ids(&order_by_app(windows)), vec![5, 4, 2, 3, 1]);
+    }
+
+    #[test]
+    fn app_order_is_stable_for_identical_keys() {
+        let windows = vec![named(2, "App", "Untitled"), named(1, "app", "untitled")];
+        assert_eq!(ids(&order_by_app(windows)), vec![2, 1]);
+    }
+
+    #[test]
+    fn most_recent_minimized_can_lead() {
+        let windows = vec![win(1, false), win(2, true), win(3, true)];
+        let stamps = HashMap::from([(1, 2), (2, 3), (3, 4)]);
+        assert_eq!(ids(&interleave_minimized(windows, &stamps)), vec![3, 2, 1]);
+    }
+}
@@ -13,7 +13,14 @@ impl WindowDelegate {
             return;
         }
         let current = self.selected_index.unwrap_or(0);
-        self.selected_index = Some((current + 1) % self.windows.len());
+        let last = self.windows.len() - 1;
+        self.selected_index = Some(if current < last {
+            current + 1
+        } else if self.cycle_wrap {
+            0
+        } else {
+            last
+        });
     }
 
     pub(crate) fn select_prev(&mut self) {
@@ -21,19 +28,28 @@ impl WindowDelegate {
             return;
         }
         let current = self.selected_index.unwrap_or(0);
-        self.selected_index = Some(if current == 0 {
+        self.selected_index = Some(if current > 0 {
+            current - 1
+        } else if self.cycle_wrap {
             self.windows.len() - 1
         } else {
-            current - 1
+            0
         });
     }
 
+    pub(crate) fn select_last(&mut self) {
+        self.selected_index = self.windows.len().checked_sub(1);
+    }
+
+    /// Move toward the screen's left edge, which in rtl is forward in the grid.
     pub(crate) fn select_left(&mut self, columns: usize) {
-        self.move_in_grid(GridDirection::Left, columns);
+        let direction = if self.rtl { GridDirection::Right } else { GridDirection::Left };
+        self.move_in_grid(direction, columns);
     }
 
     pub(crate) fn select_right(&mut self, columns: usize) {
-        self.move_in_grid(GridDirection::Right, columns);
+        let direction = if self.rtl { GridDirection::Left } else { GridDirection::Right };
+        self.move_in_grid(direction, columns);
     }
 
     pub(crate) fn select_up(&mut self, columns: usize) {
@@ -104,3 +120,232 @@ impl WindowDelegate {
         self.selected_index = Some(next);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AltTabConfig;
+    use crate::platform::WindowInfo;
+    use std::collections::HashMap;
+
+    fn delegate(count: usize, cycle_wrap: bool) -> WindowDelegate {
+        let windows = (0..count as u32)
+            .map(|id| WindowInfo {
+                id,
+                title: format!("window {id}"),
+                app_name: "app".to_string(),
+                preview_path: None,
+                icon: None,
+                x: 0.0,
+                y: 0.0,
+                width: 100.0,
+                height: 100.0,
+                is_minimized: false,
+                is_off_space: false,
+                has_unsaved_changes: false,
+            })
+            .collect();
+        let config = AltTabConfig { cycle_wrap, ..AltTabConfig::default() };
+        WindowDelegate::new_with_previews(windows, &config, HashMap::new(), HashMap::new())
+    }
+
+    #[test]
+    fn next_wraps_from_last_to_first() {
+        let mut d = delegate(3, true);
+        d.selected_index = Some(2);
+        d.select_next();
+        assert_eq!(d.selected_index, Some(0));
+    }
+
+    #[test]
+    fn prev_wraps_from_first_to_last() {
+        let mut d = delegate(3, true);
+        d.selected_index = Some(0);
+        d.select_prev();
+        assert_eq!(d.selected_index, Some(2));
+    }
+
+    #[test]
+    fn next_stops_at_last_without_wrap() {
+        let mut d = delegate(3, false);
+        d.selected_index = Some(2);
+        d.select_next();
+        assert_eq!(d.selected_index, Some(2));
+    }
+
+    #[test]
+    fn prev_stops_at_first_without_wrap() {
+        let mut d = delegate(3, false);
+        d.selected_index = Some(0);
+        d.select_prev();
+        assert_eq!(d.selected_index, Some(0));
+    }
+
+    /// Selection after moving `direction` once from `from` in a `count`-card grid.
+    fn moved(count: usize, cols: usize, from: usize, direction: GridDirection) -> usize {
+        let mut d = delegate(count, false);
+        d.selected_index = Some(from);
+        d.move_in_grid(direction, cols);
+        d.selected_index.unwrap()
+    }
+
+    #[test]
+    fn down_into_short_last_row_clamps_to_its_last_card() {
+        // 7 in 3 columns: [0 1 2] [3 4 5] [6]
+        assert_eq!(moved(7, 3, 3, GridDirection::Down), 6);
+        assert_eq!(moved(7, 3, 4, GridDirection::Down), 6);
+        assert_eq!(moved(7, 3, 5, GridDirection::Down), 6);
+        // 8 in 3 columns: [0 1 2] [3 4 5] [6 7]
+        assert_eq!(moved(8, 3, 3, GridDirection::Down), 6);
+        assert_eq!(moved(8, 3, 4, GridDirection::Down), 7);
+        assert_eq!(moved(8, 3, 5, GridDirection::Down), 7);
+        // 5 in 4 columns: [0 1 2 3] [4]
+        assert_eq!(moved(5, 4, 3, GridDirection::Down), 4);
+    }
+
+    #[test]
+    fn up_from_short_last_row_keeps_column() {
+        assert_eq!(moved(7, 3, 6, GridDirection::Up), 3);
+        assert_eq!(moved(8, 3, 6, GridDirection::Up), 3);
+        assert_eq!(moved(8, 3, 7, GridDirection::Up), 4);
+        assert_eq!(moved(5, 4, 4, GridDirection::Up), 0);
+        assert_eq!(moved(9, 4, 5, GridDirection::Up), 1);
+    }
+
+    #[test]
+    fn up_and_down_stop_at_grid_edges() {
+        assert_eq!(moved(8, 3, 1, GridDirection::Up), 1);
+        assert_eq!(moved(8, 3, 7, GridDirection::Down), 7);
+        assert_eq!(moved(7, 3, 6, GridDirection::Down), 6);
+        // Full last row: 6 in 3 columns.
+        assert_eq!(moved(6, 3, 5, GridDirection::Down), 5);
+        assert_eq!(moved(6, 3, 2, GridDirection::Down), 5);
+    }
+
+    #[test]
+    fn left_and_right_stay_within_the_row() {
+        assert_eq!(moved(7, 3, 2, GridDirection::Right), 2);
+        assert_eq!(moved(7, 3, 3, GridDirection::Left), 3);
+        assert_eq!(moved(7, 3, 4, GridDirection::Right), 5);
+        assert_eq!(moved(7, 3, 4, GridDirection::Left), 3);
+        // The short last row ends at the last card, not at the column count.
+        assert_eq!(moved(8, 3, 6, GridDirection::Right), 7);
+        assert_eq!(moved(8, 3, 7, GridDirection::Right), 7);
+        assert_eq!(moved(8, 3, 7, GridDirection::Left), 6);
+        assert_eq!(moved(7, 3, 6, GridDirection::Left), 6);
+        assert_eq!(moved(7, 3, 6, GridDirection::Right), 6);
+    }
+
+    #[test]
+    fn columns_are_clamped_to_window_count() {
+        // 3 in 5 columns is a single row of 3.
+        assert_eq!(moved(3, 5, 2, GridDirection::Right), 2);
+        assert_eq!(moved(3, 5, 1, GridDirection::Down), 1);
+        assert_eq!(moved(3, 5, 1, GridDirection::Up), 1);
+        // Zero columns behaves like a single column.
+        assert_eq!(moved(3, 0, 0, GridDirection::Down), 1);
+        assert_eq!(moved(3, 0, 2, GridDirection::Up), 1);
+        assert_eq!(moved(3, 0, 1, GridDirection::Right), 1);
+    }
+
+    #[test]
+    fn out_of_range_selection_is_clamped_before_moving() {
+        // 7 in 3 columns: a stale index of 10 is treated as 6.
+        assert_eq!(moved(7, 3, 10, GridDirection::Up), 3);
+        let mut d = delegate(7, false);
+        d.selected_index = None;
+        d.select_down(3);
+        assert_eq!(d.selected_index, Some(3));
+    }
+
+    #[test]
+    fn public_grid_moves_match_move_in_grid() {
+        let mut d = delegate(8, false);
+        d.selected_index = Some(4);
+        d.select_down(3);
+        assert_eq!(d.selected_index, Some(7));
+        d.select_left(3);
+        assert_eq!(d.selected_index, Some(6));
+        d.select_up(3);
+        assert_eq!(d.selected_index, Some(3));
+        d.select_right(3);
+        assert_eq!(d.selected_index, Some(4));
+    }
+
+    #[test]
+    fn select_moves_match_move_in_grid() {
+        // Arrow keys and vim hjkl both land in select_*; each must agree with
+        // move_in_grid for the same column count.
+        for cols in [1, 3, 4, 5] {
+            for from in [0, 4, 10] {
+                let moves: [(fn(&mut WindowDelegate, usize), GridDirection); 4] = [
+                    (WindowDelegate::select_left, GridDirection::Left),
+                    (WindowDelegate::select_right, GridDirection::Right),
+                    (WindowDelegate::select_up, GridDirection::Up),
+                    (WindowDelegate::select_down, GridDirection::Down),
+                ];
+                for (select, direction) in moves {
+                    let mut expected = delegate(11, false);
+                    expected.selected_index = Some(from);
+                    expected.move_in_grid(direction, cols);
+                    let mut d = delegate(11, false);
+                    d.selected_index = Some(from);
+                    select(&mut d, cols);
+                    assert_eq!(d.selected_index, expected.selected_index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rtl_mirrors_left_and_right() {
+        let mut d = delegate(7, false);
+        d.rtl = true;
+        d.selected_index = Some(4);
+        d.select_left(3);
+        assert_eq!(d.selected_index, Some(5));
+        d.select_left(3);
+        assert_eq!(d.selected_index, Some(5));
+        d.select_right(3);
+        assert_eq!(d.selected_index, Some(4));
+        d.select_right(3);
+        assert_eq!(d.selected_index, Some(3));
+        d.select_right(3);
+        assert_eq!(d.selected_index, Some(3));
+        // Up/down are unaffected.
+        d.select_down(3);
+        assert_eq!(d.selected_index, Some(6));
+    }
+
+    #[test]
+    fn refresh_keeps_selected_window_when_it_moves() {
+        let mut d = delegate(4, false);
+        d.selected_index = Some(2);
+        let mut refreshed = d.windows.clone();
+        // Window 0 closes, so window 2 moves up to index 1.
+        refreshed.remove(0);
+        d.set_windows(refreshed, false);
+        assert_eq!(d.selected_window_id(), Some(2));
+        assert_eq!(d.selected_index, Some(1));
+    }
+
+    #[test]
+    fn refresh_clamps_index_when_selected_window_is_gone() {
+        let mut d = delegate(4, false);
+        d.selected_index = Some(3);
+        let remaining = d.windows[..2].to_vec();
+        d.set_windows(remaining, false);
+        assert_eq!(d.selected_index, Some(1));
+    }
+
+    #[test]
+    fn single_window_stays_selected() {
+        for wrap in [true, false] {
+            let mut d = delegate(1, wrap);
+            d.select_next();
+            assert_eq!(d.selected_index, Some(0));
+            d.select_prev();
+            assert_eq!(d.selected_index, Some(0));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::config::QuickSelect;
+
+/// Cards reachable by a single digit key.
+const DIGIT_SHORTCUTS: usize = 9;
+
+/// Keys used for home-row hints, in the order single-key hints are handed out.
+/// `l` is left out: it toggles the layout, or moves right with vim navigation.
+const HOME_ROW_KEYS: &[char] = &['a', 's', 'd', 'f', 'g', 'h', 'j', 'k'];
+
+/// Home-row keys free for hints; with vim navigation `h` / `j` / `k` move the selection.
+pub(crate) fn hint_keys(vim_navigation: bool) -> &'static [char] {
+    if vim_navigation {
+        &HOME_ROW_KEYS[..5]
+    } else {
+        HOME_ROW_KEYS
+    }
+}
+
+/// Result of matching typed hint keys against the cards' hints.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum HintMatch {
+    /// `typed` is a card's full hint.
+    Complete(usize),
+    /// `typed` starts at least one hint; wait for more keys.
+    Partial,
+    /// No hint starts with `typed`.
+    None,
+}
+
+/// Key sequence that activates each of `count` cards, in card order.
+/// Cards past the scheme's reach get no label.
+pub(crate) fn shortcut_labels(scheme: QuickSelect, count: usize, vim_navigation: bool) -> Vec<String> {
+    match scheme {
+        QuickSelect::None => Vec::new(),
+        QuickSelect::Numbers => (1..=count.min(DIGIT_SHORTCUTS)).map(|n| n.to_string()).collect(),
+        QuickSelect::HomeRow => home_row_hints(count, hint_keys(vim_navigation)),
+    }
+}
+
+/// Card index activated by a digit key, the inverse of the `Numbers` labels.
+pub(crate) fn shortcut_index(key: &str) -> Option<usize> {
+    let digit: usize = key.parse().ok()?;
+    (1..=DIGIT_SHORTCUTS).contains(&digit).then(|| digit - 1)
+}
+
+/// Prefix-free hints of minimal length for `count` cards, Vimium style:
+/// single keys while they last, then the earliest single keys are split into
+/// two-key sequences (and so on) until there are enough.
+pub(crate) fn home_row_hints(count: usize, keys: &[char]) -> Vec<String> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let mut hints = vec![String::new()];
+    let mut offset = 0;
+    while hints.len() - offset < count || hints.len() == 1 {
+        let prefix = hints[offset].clone();
+        offset += 1;
+        hints.extend(keys.iter().map(|key| format!("{prefix}{key}")));
+    }
+    hints.drain(..offset);
+    hints.truncate(count);
+    hints
+}
+
+/// Match `typed` against `hints`.
+pub(crate) fn match_hint(hints: &[String], typed: &str) -> HintMatch {
+    if let Some(ix) = hints.iter().position(|hint| hint == typed) {
+        HintMatch::Complete(ix)
+    } else if hints.iter().any(|hint| hint.starts_with(typed)) {
+        HintMatch::Partial
+    } else {
+        HintMatch::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_cover_first_nine_cards() {
+        let labels = shortcut_labels(QuickSelect::Numbers, 12, false);
+        assert_eq!(labels.len(), 9);
+        assert_eq!(labels[0], "1");
+        assert_eq!(labels[8], "9");
+        assert!(shortcut_labels(QuickSelect::None, 12, false).is_empty());
+    }
+
+    #[test]
+    fn digit_index_round_trips_through_label() {
+        for (i, label) in shortcut_labels(QuickSelect::Numbers, 9, false).iter().enumerate() {
+            assert_eq!(shortcut_index(label), Some(i));
+        }
+        assert_eq!(shortcut_index("0"), None);
+        assert_eq!(shortcut_index("10"), None);
+        assert_eq!(shortcut_index("a"), None);
+    }
+
+    #[test]
+    fn home_row_uses_single_keys_when_they_suffice() {
+        let keys = hint_keys(false);
+        assert_eq!(home_row_hints(3, keys), ["a", "s", "d"]);
+        assert_eq!(home_row_hints(8, keys).len(), 8);
+        assert!(home_row_hints(8, keys).iter().all(|h| h.len() == 1));
+        assert!(home_row_hints(0, keys).is_empty());
+    }
+
+    #[test]
+    fn home_row_overflow_splits_earliest_key() {
+        let hints = home_row_hints(9, hint_keys(false));
+        assert_eq!(hints, ["s", "d", "f", "g", "h", "j", "k", "aa", "as"]);
+    }
+
+    #[test]
+    fn hints_leave_navigation_and_layout_keys_alone() {
+        let plain = shortcut_labels(QuickSelect::HomeRow, 100, false);
+        assert!(plain.iter().all(|h| !h.contains('l')));
+        let vim = shortcut_labels(QuickSelect::HomeRow, 100, true);
+        assert_eq!(vim.len(), 100);
+        assert!(vim.iter().all(|h| !h.contains(['h', 'j', 'k', 'l'])));
+    }
+
+    #[test]
+    fn home_row_hints_are_unique_and_prefix_free() {
+        for count in [1, 8, 9, 17, 40, 64, 65, 100] {
+            let hints = home_row_hints(count, hint_keys(false));
+            assert_eq!(hints.len(), count);
+            for (i, a) in hints.iter().enumerate() {
+                for (j, b) in hints.iter().enumerate() {
+                    assert!(i == j || !b.starts_with(a.as_str()), "{a} prefixes {b} for {count}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn match_hint_waits_for_full_sequence() {
+        let hints = home_row_hints(9, hint_keys(false));
+        assert_eq!(match_hint(&hints, "a"), HintMatch::Partial);
+        assert_eq!(match_hint(&hints, "as"), HintMatch::Complete(8));
+        assert_eq!(match_hint(&hints, "s"), HintMatch::Complete(0));
+        assert_eq!(match_hint(&hints, "ad"), HintMatch::None);
+    }
+}
@@ -0,0 +1,142 @@
+//! `--doctor`: one-shot health report covering the most common failure modes.
+
+use crate::config::{load_alt_tab_config, PreviewQuality};
+use crate::daemon::{self, DumpKind};
+use crate::layout::{PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH};
+use crate::platform;
+use std::sync::mpsc;
+
+#[derive(Default)]
+struct Report {
+    critical_failures: usize,
+}
+
+impl Report {
+    fn check(&mut self, ok: bool, critical: bool, label: &str, detail: &str) {
+        let tag = match (ok, critical) {
+            (true, _) => "ok  ",
+            (false, true) => "FAIL",
+            (false, false) => "warn",
+        };
+        if !ok && critical {
+            self.critical_failures += 1;
+        }
+        if detail.is_empty() {
+            println!("[{}] {}", tag, label);
+        } else {
+            println!("[{}] {} ({})", tag, label, detail);
+        }
+    }
+}
+
+/// Print the report. Returns false if any critical check failed.
+pub fn run() -> bool {
+    let mut report = Report::default();
+    println!("alt-tab doctor ({})", std::env::consts::OS);
+
+    if daemon::ping() {
+        report.check(true, true, "daemon socket", "daemon is running");
+    } else {
+        let (tx, _rx) = mpsc::channel();
+        let bindable = daemon::start_listener(tx);
+        if bindable {
+            daemon::cleanup();
+        }
+        report.check(bindable, true, "daemon socket", if bindable { "bindable" } else { "cannot bind" });
+    }
+
+    for (label, ok) in platform::environment_checks() {
+        report.check(ok, true, label, "");
+    }
+
+    let windows = platform::get_open_windows(false, false);
+    report.check(
+        !windows.is_empty(),
+        true,
+        "window enumeration",
+        &format!("{} windows", windows.len()),
+    );
+
+    match qol_plugin_api::PlatformStateClient::from_env().get_state() {
+        Some(state) => report.check(
+            !state.monitors.is_empty(),
+            false,
+            "monitors",
+            &format!("{} detected", state.monitors.len()),
+        ),
+        None => report.check(false, false, "monitors", "qol-tray runtime not reachable"),
+    }
+
+    match windows.iter().find(|w| !w.is_minimized) {
+        Some(win) => {
+            let captured = platform::capture_previews_cg(
+                &[(0, win.id)],
+                PREVIEW_MAX_WIDTH,
+                PREVIEW_MAX_HEIGHT,
+                PreviewQuality::Fast,
+                false,
+                load_alt_tab_config().display.screen_capture_kit,
+            );
+            let ok = captured.into_iter().any(|(_, img)| img.is_some());
+            // Linux has no preview capture; cards fall back to icons there.
+            report.check(ok, false, "test capture", &format!("window \"{}\"", win.title));
+        }
+        None => report.check(false, false, "test capture", "no visible window to capture"),
+    }
+
+    if report.critical_failures == 0 {
+        println!("all critical checks passed");
+        true
+    } else {
+        println!("{} critical check(s) failed", report.critical_failures);
+        false
+    }
+}
+
+/// How long `--mru` and `--list` wait for the daemon to write their dump.
+const DUMP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// `--mru`: ask the running daemon for its focus-recency list and print it as
+/// JSON. Returns false if no daemon answered.
+pub fn print_mru() -> bool {
+    print_dump(
+        DumpKind::Mru,
+        "alt-tab daemon is not running; focus recency is only tracked while it runs",
+    )
+}
+
+/// `--list`: ask the running daemon for its current window list and print it
+/// as JSON. Returns false if no daemon answered.
+pub fn print_windows() -> bool {
+    print_dump(DumpKind::Windows, "alt-tab daemon is not running")
+}
+
+/// Reserve a reply file, have the daemon fill it, then print it.
+fn print_dump(kind: DumpKind, not_running: &str) -> bool {
+    let path = match crate::dump::reserve(kind.verb()) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("cannot create the {} reply file: {}", kind.verb(), e);
+            return false;
+        }
+    };
+    if !daemon::send_dump(kind, &path) {
+        let _ = std::fs::remove_file(&path);
+        eprintln!("{}", not_running);
+        return false;
+    }
+    let start = std::time::Instant::now();
+    while start.elapsed() < DUMP_TIMEOUT {
+        if let Ok(json) = std::fs::read_to_string(&path) {
+            if !json.is_empty() {
+                let _ = std::fs::remove_file(&path);
+                println!("{}", json);
+                return true;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    let _ = std::fs::remove_file(&path);
+    eprintln!("alt-tab daemon did not answer the {} request", kind.verb());
+    false
+}
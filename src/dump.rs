@@ -0,0 +1,43 @@
+//! Reply handoff for `--mru` and `--list`. The daemon socket only carries
+//! commands, so the CLI reserves an owner-only file in a private per-user
+//! directory and the daemon fills it. The daemon writes nowhere else.
+
+use crate::private_files;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+fn private_dir() -> io::Result<PathBuf> {
+    let dir = private_files::dir("dumps")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no runtime or cache directory"))?;
+    private_files::ensure_dir(&dir)
+}
+
+/// CLI side: create the empty file the daemon should answer `kind` into.
+pub(crate) fn reserve(kind: &str) -> io::Result<PathBuf> {
+    let path = private_dir()?.join(format!("{}-{}.json", kind, std::process::id()));
+    let _ = fs::remove_file(&path);
+    private_files::create(&path)?;
+    Ok(path)
+}
+
+/// Daemon side: whether `path` is a regular file directly inside the dump directory.
+pub(crate) fn is_reserved(path: &Path) -> bool {
+    let (Ok(dir), Some(parent)) = (private_dir(), path.parent()) else {
+        return false;
+    };
+    parent.canonicalize().is_ok_and(|p| p == dir)
+        && fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_file())
+}
+
+/// Daemon side: fill a reserved file. Written aside and renamed so the CLI
+/// never reads a partial reply.
+pub(crate) fn write(path: &Path, json: &str) -> io::Result<()> {
+    if !is_reserved(path) {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "not a reserved dump file"));
+    }
+    let partial = path.with_extension("partial");
+    let _ = fs::remove_file(&partial);
+    private_files::create(&partial)?.write_all(json.as_bytes())?;
+    fs::rename(&partial, path)
+}
@@ -0,0 +1,73 @@
+//! Optional one-line JSON records of picker events (`daemon.event_log`), for
+//! scripts that react to switches. Emitting only queues the event; a writer
+//! thread does the I/O so the UI thread never waits on it.
+
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{mpsc, OnceLock};
+
+static SINK: OnceLock<mpsc::Sender<AltTabEvent>> = OnceLock::new();
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum AltTabEvent {
+    Activate { window_id: u32, app: String, title: String },
+}
+
+/// Start writing events to `path`, appended to, or stdout for `-`. Called once
+/// at daemon start; later calls are ignored.
+pub(crate) fn start(path: &Path) {
+    let out: Box<dyn Write + Send> = if path == Path::new("-") {
+        Box::new(std::io::stdout())
+    } else {
+        match std::fs::OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                eprintln!("[alt-tab] cannot open event log {}: {}", path.display(), e);
+                return;
+            }
+        }
+    };
+    let (tx, rx) = mpsc::channel();
+    if SINK.set(tx).is_err() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mut out = out;
+        for event in rx {
+            let Ok(line) = serde_json::to_string(&event) else {
+                continue;
+            };
+            if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+                eprintln!("[alt-tab] event log write failed — stopping");
+                return;
+            }
+        }
+    });
+}
+
+/// Queue `event` for the log; a no-op unless `start` succeeded.
+pub(crate) fn emit(event: AltTabEvent) {
+    if let Some(tx) = SINK.get() {
+        let _ = tx.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activate_serializes_as_flat_record() {
+        let event = AltTabEvent::Activate {
+            window_id: 42,
+            app: "Terminal".into(),
+            title: "~/src".into(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"activate","window_id":42,"app":"Terminal","title":"~/src"}"#
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Process-wide record of when each window was last frontmost: the source of
+//! MRU card order (`order: "mru"`, `minimized_sort: "by_mru"`), `restore_last`,
+//! the `--mru` dump and `--switch-recent-on-monitor`. Picker switches and
+//! tap-to-previous record into it alongside the background refresh. Stamps are
+//! a monotonic counter, not wall time.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+static STAMPS: Mutex<Option<FocusStamps>> = Mutex::new(None);
+/// Window last activated from the picker, for `open_behavior: restore_last`.
+static LAST_ACTIVATED: Mutex<Option<u32>> = Mutex::new(None);
+
+#[derive(Default)]
+struct FocusStamps {
+    next: u64,
+    by_window: HashMap<u32, u64>,
+    /// Wall-clock time of each stamp, for `--mru` only.
+    at: HashMap<u32, SystemTime>,
+}
+
+/// Record `window_id` as the most recently focused window. Repeated calls for
+/// the window that is already newest are no-ops.
+pub(crate) fn note_frontmost(window_id: u32) {
+    let Ok(mut guard) = STAMPS.lock() else {
+        return;
+    };
+    let stamps = guard.get_or_insert_with(FocusStamps::default);
+    if stamps.next > 0 && stamps.by_window.get(&window_id) == Some(&(stamps.next - 1)) {
+        return;
+    }
+    stamps.by_window.insert(window_id, stamps.next);
+    stamps.at.insert(window_id, SystemTime::now());
+    stamps.next += 1;
+}
+
+/// Record `window_id` as activated from the picker (and so also frontmost).
+pub(crate) fn note_activated(window_id: u32) {
+    if let Ok(mut last) = LAST_ACTIVATED.lock() {
+        *last = Some(window_id);
+    }
+    note_frontmost(window_id);
+}
+
+pub(crate) fn last_activated() -> Option<u32> {
+    LAST_ACTIVATED.lock().ok().and_then(|last| *last)
+}
+
+/// Current stamps; higher means more recently focused.
+pub(crate) fn snapshot() -> HashMap<u32, u64> {
+    STAMPS
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|s| s.by_window.clone()))
+        .unwrap_or_default()
+}
+
+/// (window id, stamp, focused at) for every stamped window, most recent first.
+pub(crate) fn entries() -> Vec<(u32, u64, SystemTime)> {
+    let Ok(guard) = STAMPS.lock() else {
+        return Vec::new();
+    };
+    let Some(stamps) = guard.as_ref() else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = stamps
+        .by_window
+        .iter()
+        .map(|(&id, &stamp)| (id, stamp, stamps.at.get(&id).copied().unwrap_or(SystemTime::UNIX_EPOCH)))
+        .collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1));
+    entries
+}
+
+/// Drop stamps for windows that no longer exist.
+pub(crate) fn retain(live_ids: &std::collections::HashSet<u32>) {
+    if let Ok(mut guard) = STAMPS.lock() {
+        if let Some(stamps) = guard.as_mut() {
+            stamps.by_window.retain(|id, _| live_ids.contains(id));
+            stamps.at.retain(|id, _| live_ids.contains(id));
+        }
+    }
+}
@@ -1,13 +1,38 @@
-use crate::platform::RgbaImage;
+use crate::platform::{RgbaImage, WindowInfo};
+use crate::preview::dimensions_match;
 use gpui::RenderImage;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// One window per app that has no cached icon yet. Icons are fetched only for
+/// these, the first time the app is about to be shown, and then kept.
+pub(crate) fn windows_needing_icons<V>(
+    windows: &[WindowInfo],
+    cached: &HashMap<String, V>,
+) -> Vec<WindowInfo> {
+    let mut seen = HashSet::new();
+    windows
+        .iter()
+        .filter(|w| !cached.contains_key(&w.app_name) && seen.insert(w.app_name.as_str()))
+        .cloned()
+        .collect()
+}
+
 pub(crate) fn build_icon_cache(
     raw_icons: HashMap<String, RgbaImage>,
 ) -> HashMap<String, Arc<RenderImage>> {
     let mut cache: HashMap<String, Arc<RenderImage>> = HashMap::new();
     for (app_name, icon) in raw_icons {
+        if !dimensions_match(icon.data.len(), icon.width, icon.height) {
+            eprintln!(
+                "[alt-tab/icon] skipping icon for {}: {} bytes for {}x{}",
+                app_name,
+                icon.data.len(),
+                icon.width,
+                icon.height
+            );
+            continue;
+        }
         let buf = image::ImageBuffer::<image::Rgba<u8>, Vec<u8>>::from_raw(
             icon.width as u32,
             icon.height as u32,
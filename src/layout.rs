@@ -1,3 +1,4 @@
+use crate::config::{DisplayConfig, LayoutMode};
 use gpui::*;
 
 pub const GRID_GAP: f32 = 14.0;
@@ -9,10 +10,18 @@ pub const GRID_PREVIEW_HEIGHT: f32 = 114.0;
 pub const HEADER_HEIGHT: f32 = 42.0;
 /// Height of the hotkey hints bar (py_2 + text_xs + border_b_1).
 pub const HOTKEY_HINTS_HEIGHT: f32 = 48.0;
+/// Height of the quick action footer (py_2 + button row + border_t_1).
+pub const ACTION_BAR_HEIGHT: f32 = 44.0;
+/// Height of the recent-windows strip (py_1 + chip row + border_b_1).
+pub const RECENT_STRIP_HEIGHT: f32 = 36.0;
 pub const PREVIEW_MAX_WIDTH: usize = GRID_PREVIEW_WIDTH as usize;
 pub const PREVIEW_MAX_HEIGHT: usize = GRID_PREVIEW_HEIGHT as usize;
 pub const GRID_RENDER_PADDING_X_TOTAL: f32 = 40.0;
 pub const GRID_RENDER_GAP_X: f32 = 12.0;
+/// Height of one list-layout row (py_1 + 16px icon + text_sm).
+pub const LIST_ROW_HEIGHT: f32 = 30.0;
+/// Vertical gap between list rows (gap_1).
+pub const LIST_ROW_GAP: f32 = 4.0;
 
 pub fn preferred_column_count(window_count: usize, max_columns: usize) -> usize {
     let count = window_count.max(1);
@@ -26,40 +35,289 @@ pub fn preferred_column_count(window_count: usize, max_columns: usize) -> usize
     cols
 }
 
-pub fn picker_dimensions(window_count: usize, max_columns: usize, monitor_size: Option<(f32, f32)>, show_hotkey_hints: bool) -> (f32, f32) {
+/// Card and chrome sizes the picker layout is computed from.
+#[derive(Debug, Clone, Copy)]
+pub struct GridMetrics {
+    pub card_width: f32,
+    pub card_height: f32,
+    pub gap: f32,
+    pub padding: f32,
+    pub header_height: f32,
+    pub hints_height: f32,
+    pub action_bar_height: f32,
+    pub recent_strip_height: f32,
+}
+
+impl GridMetrics {
+    pub const DEFAULT: Self = Self {
+        card_width: GRID_CARD_WIDTH,
+        card_height: GRID_CARD_HEIGHT,
+        gap: GRID_GAP,
+        padding: GRID_PADDING,
+        header_height: HEADER_HEIGHT,
+        hints_height: HOTKEY_HINTS_HEIGHT,
+        action_bar_height: ACTION_BAR_HEIGHT,
+        recent_strip_height: RECENT_STRIP_HEIGHT,
+    };
+
+    /// DEFAULT with cards `card_scale` times the base size; gaps and bars keep theirs.
+    pub fn scaled(card_scale: f32) -> Self {
+        Self {
+            card_width: GRID_CARD_WIDTH * card_scale,
+            card_height: GRID_CARD_HEIGHT * card_scale,
+            ..Self::DEFAULT
+        }
+    }
+}
+
+/// Capture size for previews drawn at `card_scale`, so they are never upscaled.
+pub fn preview_max_size(card_scale: f32) -> (usize, usize) {
+    (
+        (GRID_PREVIEW_WIDTH * card_scale).round() as usize,
+        (GRID_PREVIEW_HEIGHT * card_scale).round() as usize,
+    )
+}
+
+/// Optional bars above and below the grid that add to the picker height.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Chrome {
+    pub hotkey_hints: bool,
+    pub action_bar: bool,
+    pub recent_strip: bool,
+}
+
+impl Chrome {
+    pub fn from_display(display: &DisplayConfig) -> Self {
+        Self {
+            hotkey_hints: display.show_hotkey_hints,
+            action_bar: display.show_action_bar,
+            recent_strip: display.show_recent_strip,
+        }
+    }
+
+    fn height(&self, metrics: &GridMetrics) -> f32 {
+        let bar = |on: bool, h: f32| if on { h } else { 0.0 };
+        bar(self.hotkey_hints, metrics.hints_height)
+            + bar(self.action_bar, metrics.action_bar_height)
+            + bar(self.recent_strip, metrics.recent_strip_height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickerLayout {
+    pub columns: usize,
+    pub width: f32,
+    pub height: f32,
+}
+
+const MIN_PICKER_WIDTH: f32 = 720.0;
+const MIN_PICKER_HEIGHT: f32 = 320.0;
+/// Fallback bounds when the monitor size is unknown.
+const FALLBACK_MAX_SIZE: (f32, f32) = (1820.0, 980.0);
+
+/// Columns and window size for `window_count` cards. The result never exceeds
+/// 90% of `monitor_size`; on very small monitors that wins over the minimum size.
+pub fn compute_picker_layout(
+    window_count: usize,
+    max_columns: usize,
+    monitor_size: Option<(f32, f32)>,
+    chrome: Chrome,
+    metrics: &GridMetrics,
+) -> PickerLayout {
     let count = window_count.max(1);
     let cols = preferred_column_count(count, max_columns);
-    let width = GRID_PADDING * 2.0
-        + cols as f32 * GRID_CARD_WIDTH
-        + cols.saturating_sub(1) as f32 * GRID_GAP
+    let width = metrics.padding * 2.0
+        + cols as f32 * metrics.card_width
+        + cols.saturating_sub(1) as f32 * metrics.gap
         + 24.0;
-    let hints_height = if show_hotkey_hints { HOTKEY_HINTS_HEIGHT } else { 0.0 };
-    let height = picker_height_for(count, cols) + hints_height;
+    let height = grid_height(count, cols, metrics) + chrome.height(metrics);
 
+    let (width, height) = clamp_to_monitor(width, height, monitor_size);
+    PickerLayout { columns: cols, width, height }
+}
+
+/// Single-column list sized for `window_count` rows, clamped like the grid.
+pub fn compute_list_layout(
+    window_count: usize,
+    monitor_size: Option<(f32, f32)>,
+    chrome: Chrome,
+    metrics: &GridMetrics,
+) -> PickerLayout {
+    let count = window_count.max(1);
+    let height = metrics.header_height
+        + metrics.padding * 2.0
+        + count as f32 * LIST_ROW_HEIGHT
+        + count.saturating_sub(1) as f32 * LIST_ROW_GAP
+        + chrome.height(metrics);
+    let (width, height) = clamp_to_monitor(MIN_PICKER_WIDTH, height, monitor_size);
+    PickerLayout { columns: 1, width, height }
+}
+
+fn clamp_to_monitor(width: f32, height: f32, monitor_size: Option<(f32, f32)>) -> (f32, f32) {
     let (max_w, max_h) = monitor_size
         .map(|(w, h)| (w * 0.9, h * 0.9))
-        .unwrap_or((1820.0, 980.0));
-    (width.clamp(720.0, max_w), height.clamp(320.0, max_h))
+        .unwrap_or(FALLBACK_MAX_SIZE);
+    (
+        width.max(MIN_PICKER_WIDTH.min(max_w)).min(max_w),
+        height.max(MIN_PICKER_HEIGHT.min(max_h)).min(max_h),
+    )
+}
+
+pub fn picker_dimensions(
+    window_count: usize,
+    max_columns: usize,
+    monitor_size: Option<(f32, f32)>,
+    chrome: Chrome,
+    layout: LayoutMode,
+    card_scale: f32,
+) -> (f32, f32) {
+    let metrics = GridMetrics::scaled(card_scale);
+    let layout = match layout {
+        LayoutMode::Grid => compute_picker_layout(window_count, max_columns, monitor_size, chrome, &metrics),
+        LayoutMode::List => compute_list_layout(window_count, monitor_size, chrome, &metrics),
+    };
+    (layout.width, layout.height)
 }
 
-pub fn picker_height_for(window_count: usize, columns: usize) -> f32 {
+fn grid_height(window_count: usize, columns: usize, metrics: &GridMetrics) -> f32 {
     let count = window_count.max(1);
     let cols = columns.max(1);
-    let rows = (count + cols - 1) / cols;
-    HEADER_HEIGHT
-        + GRID_PADDING * 2.0
-        + rows as f32 * GRID_CARD_HEIGHT
-        + rows.saturating_sub(1) as f32 * GRID_GAP
+    let rows = count.div_ceil(cols);
+    metrics.header_height
+        + metrics.padding * 2.0
+        + rows as f32 * metrics.card_height
+        + rows.saturating_sub(1) as f32 * metrics.gap
 }
 
-pub fn rendered_column_count(window: &Window, total_items: usize) -> usize {
+/// Columns the flex-wrapped grid actually renders at `window_width`.
+pub fn columns_for_width(window_width: f32, total_items: usize, card_width: f32) -> usize {
     if total_items <= 1 {
         return total_items.max(1);
     }
+    let usable = (window_width - GRID_RENDER_PADDING_X_TOTAL).max(card_width);
+    let cols = ((usable + GRID_RENDER_GAP_X) / (card_width + GRID_RENDER_GAP_X)).floor();
+    (cols as usize).max(1).min(total_items)
+}
 
+pub fn rendered_column_count(window: &Window, total_items: usize, card_scale: f32) -> usize {
     let bounds = window.window_bounds().get_bounds();
-    let width = bounds.size.width.to_f64() as f32;
-    let usable = (width - GRID_RENDER_PADDING_X_TOTAL).max(GRID_CARD_WIDTH);
-    let cols = ((usable + GRID_RENDER_GAP_X) / (GRID_CARD_WIDTH + GRID_RENDER_GAP_X)).floor();
-    (cols as usize).max(1).min(total_items)
+    columns_for_width(bounds.size.width.to_f64() as f32, total_items, GRID_CARD_WIDTH * card_scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Option<(f32, f32)> = Some((1920.0, 1080.0));
+    const HINTS: Chrome = Chrome { hotkey_hints: true, action_bar: false, recent_strip: false };
+
+    fn layout(count: usize, max_columns: usize) -> PickerLayout {
+        compute_picker_layout(count, max_columns, MONITOR, HINTS, &GridMetrics::DEFAULT)
+    }
+
+    #[test]
+    fn single_window_uses_one_column_and_min_size() {
+        let l = layout(1, 6);
+        assert_eq!(l.columns, 1);
+        assert_eq!(l.width, MIN_PICKER_WIDTH);
+        assert_eq!(l.height, MIN_PICKER_HEIGHT);
+    }
+
+    #[test]
+    fn two_windows_share_a_row() {
+        let l = layout(2, 6);
+        assert_eq!(l.columns, 2);
+        assert_eq!(l.height, layout(1, 6).height);
+    }
+
+    #[test]
+    fn wraps_to_second_row_past_max_columns() {
+        let full_row = layout(4, 4);
+        let wrapped = layout(5, 4);
+        assert_eq!(full_row.columns, 4);
+        assert_eq!(wrapped.columns, 4);
+        assert_eq!(wrapped.width, full_row.width);
+        assert!(wrapped.height > full_row.height);
+    }
+
+    #[test]
+    fn max_columns_below_two_is_raised_to_two() {
+        assert_eq!(layout(10, 0).columns, 2);
+        assert_eq!(layout(10, 1).columns, 2);
+    }
+
+    #[test]
+    fn large_counts_are_capped_by_monitor() {
+        let l = layout(200, 12);
+        assert_eq!(l.columns, 12);
+        assert_eq!(l.width, 1920.0 * 0.9);
+        assert_eq!(l.height, 1080.0 * 0.9);
+    }
+
+    #[test]
+    fn rendered_columns_match_preferred_width() {
+        for count in 2..=12 {
+            let l = layout(count, 12);
+            if l.width < 1920.0 * 0.9 {
+                assert_eq!(columns_for_width(l.width, count, GRID_CARD_WIDTH), l.columns, "count={count}");
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_cards_render_the_columns_they_were_sized_for() {
+        for scale in [0.5, 0.75, 1.5, 2.0] {
+            let metrics = GridMetrics::scaled(scale);
+            for count in 2..=8 {
+                let l = compute_picker_layout(count, 12, Some((7680.0, 4320.0)), HINTS, &metrics);
+                assert_eq!(
+                    columns_for_width(l.width, count, metrics.card_width),
+                    l.columns,
+                    "scale={scale} count={count}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn preview_capture_size_follows_card_scale() {
+        assert_eq!(preview_max_size(1.0), (PREVIEW_MAX_WIDTH, PREVIEW_MAX_HEIGHT));
+        assert_eq!(preview_max_size(2.0), (PREVIEW_MAX_WIDTH * 2, PREVIEW_MAX_HEIGHT * 2));
+    }
+
+    #[test]
+    fn layout_always_fits_monitor() {
+        let monitors = [(640.0, 480.0), (800.0, 600.0), (1366.0, 768.0), (1920.0, 1080.0), (3840.0, 2160.0)];
+        for &(mw, mh) in &monitors {
+            for count in 0..=64 {
+                for max_columns in 0..=12 {
+                    for (hotkey_hints, action_bar, recent_strip) in
+                        [(false, false, false), (true, false, false), (true, true, true)]
+                    {
+                        let chrome = Chrome { hotkey_hints, action_bar, recent_strip };
+                        let l = compute_picker_layout(
+                            count,
+                            max_columns,
+                            Some((mw, mh)),
+                            chrome,
+                            &GridMetrics::DEFAULT,
+                        );
+                        assert!(l.width <= mw && l.height <= mh, "{l:?} exceeds {mw}x{mh}");
+                        assert!(l.columns >= 1);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn list_layout_grows_by_rows_and_fits_monitor() {
+        let small = compute_list_layout(20, MONITOR, HINTS, &GridMetrics::DEFAULT);
+        let large = compute_list_layout(21, MONITOR, HINTS, &GridMetrics::DEFAULT);
+        assert_eq!(small.columns, 1);
+        assert_eq!(large.height - small.height, LIST_ROW_HEIGHT + LIST_ROW_GAP);
+        let all_bars = Chrome { hotkey_hints: true, action_bar: true, recent_strip: true };
+        let capped = compute_list_layout(500, MONITOR, all_bars, &GridMetrics::DEFAULT);
+        assert_eq!(capped.height, 1080.0 * 0.9);
+    }
 }
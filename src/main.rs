@@ -2,12 +2,17 @@ mod app;
 mod config;
 mod daemon;
 mod delegate;
+mod doctor;
+mod dump;
+mod events;
+mod focus_history;
 mod icon;
 mod layout;
 mod monitor;
 mod picker;
 mod platform;
 mod preview;
+mod private_files;
 mod window_source;
 
 use crate::config::load_alt_tab_config;
@@ -25,16 +30,62 @@ fn maybe_open_settings(args: &[String]) -> bool {
     true
 }
 
+fn maybe_print_config_schema(args: &[String]) -> bool {
+    if !args.iter().any(|arg| arg == "--config-schema") {
+        return false;
+    }
+    println!("{}", config::config_schema_json());
+    true
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().skip(1).collect();
 
     if maybe_open_settings(&args) {
         return;
     }
+    if maybe_print_config_schema(&args) {
+        return;
+    }
+    if args.iter().any(|a| a == "--doctor") {
+        std::process::exit(if doctor::run() { 0 } else { 1 });
+    }
+    if args.iter().any(|a| a == "--mru") {
+        std::process::exit(if doctor::print_mru() { 0 } else { 1 });
+    }
+    if args.iter().any(|a| a == "--list") {
+        std::process::exit(if doctor::print_windows() { 0 } else { 1 });
+    }
 
     let is_show = args.iter().any(|a| a == "--show");
     let is_show_reverse = args.iter().any(|a| a == "--show-reverse");
+    let is_show_app = args.iter().any(|a| a == "--show-app");
     let is_kill = args.iter().any(|a| a == "--kill");
+    let is_switch_recent = args.iter().any(|a| a == "--switch-recent-on-monitor");
+    let profile = args
+        .iter()
+        .position(|a| a == "--profile")
+        .and_then(|ix| args.get(ix + 1))
+        .cloned();
+    let group = args
+        .iter()
+        .position(|a| a == "--group")
+        .and_then(|ix| args.get(ix + 1))
+        .cloned();
+
+    let activate = args
+        .iter()
+        .position(|a| a == "--activate")
+        .and_then(|ix| args.get(ix + 1))
+        .map(|id| id.parse::<u32>());
+    let activate = match activate {
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => {
+            eprintln!("--activate needs a numeric window id (see --list)");
+            std::process::exit(2);
+        }
+        None => None,
+    };
 
     if is_kill {
         daemon::send_kill();
@@ -42,6 +93,27 @@ fn main() {
     }
 
     // If daemon is alive, forward command and exit
+    if let Some(name) = &profile {
+        if daemon::send_profile(name) && !is_show && !is_show_reverse {
+            return;
+        }
+    }
+    if let Some(id) = activate {
+        if daemon::send_activate(id) {
+            return;
+        }
+    }
+    if is_switch_recent && daemon::send_switch_recent_on_monitor() {
+        return;
+    }
+    if let Some(name) = &group {
+        if daemon::send_group(name) {
+            return;
+        }
+    }
+    if is_show_app && daemon::send_show_app() {
+        return;
+    }
     if is_show_reverse && daemon::send_show_reverse() {
         return;
     }
@@ -50,11 +122,22 @@ fn main() {
     }
 
     // Otherwise start as daemon
+    config::set_active_profile(profile);
     let config = load_alt_tab_config();
     let (tx, rx) = mpsc::channel();
 
+    let startup_tx = tx.clone();
+    let show_on_start = is_show && group.is_none() && !is_show_app;
     if !daemon::start_listener(tx) {
-        if is_show_reverse {
+        if let Some(id) = activate {
+            daemon::send_activate(id);
+        } else if is_switch_recent {
+            daemon::send_switch_recent_on_monitor();
+        } else if let Some(name) = &group {
+            daemon::send_group(name);
+        } else if is_show_app {
+            daemon::send_show_app();
+        } else if is_show_reverse {
             daemon::send_show_reverse();
         } else if is_show {
             daemon::send_show();
@@ -62,7 +145,18 @@ fn main() {
         return;
     }
 
-    picker::run::run_app(config, rx, is_show);
+    // Queued ahead of any socket command so a cold start opens the group.
+    if let Some(name) = group {
+        let _ = startup_tx.send(daemon::Command::ShowGroup(name));
+    } else if is_show_app {
+        let _ = startup_tx.send(daemon::Command::ShowAppOnly);
+    }
+    // A cold `--activate` starts the daemon and switches once it is up.
+    if let Some(id) = activate {
+        let _ = startup_tx.send(daemon::Command::Activate(id));
+    }
+    picker::run::run_app(config, rx, show_on_start);
+    preview::clear_spilled();
     daemon::cleanup();
 }
 
@@ -1 +1,142 @@
 pub(crate) use qol_plugin_api::monitor::MonitorTracker;
+
+/// Index of the monitor (x, y, width, height) sharing the most area with
+/// `window`, or None if it overlaps none. Unlike a center-point test this
+/// settles windows straddling two monitors the way the tracker does.
+pub(crate) fn monitor_for_bounds(
+    window: (f32, f32, f32, f32),
+    monitors: &[(f32, f32, f32, f32)],
+) -> Option<usize> {
+    let (wx, wy, ww, wh) = window;
+    monitors
+        .iter()
+        .map(|&(mx, my, mw, mh)| {
+            let w = ((wx + ww).min(mx + mw) - wx.max(mx)).max(0.0);
+            let h = ((wy + wh).min(my + mh) - wy.max(my)).max(0.0);
+            w * h
+        })
+        .enumerate()
+        .filter(|(_, area)| *area > 0.0)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(ix, _)| ix)
+}
+
+/// `current_monitor_only` filter: keeps windows that `monitor_for_bounds`
+/// places on the active display, judged against every display so a window
+/// whose center falls in a gap still lands on one of them.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MonitorFilter {
+    displays: Vec<(f32, f32, f32, f32)>,
+    active: usize,
+}
+
+impl MonitorFilter {
+    /// `active` is matched to `displays` by origin, and appended if missing.
+    pub(crate) fn new(mut displays: Vec<(f32, f32, f32, f32)>, active: (f32, f32, f32, f32)) -> Self {
+        let (ax, ay, _, _) = active;
+        let found = displays
+            .iter()
+            .position(|&(x, y, _, _)| (x - ax).abs() < 1.0 && (y - ay).abs() < 1.0);
+        let active = found.unwrap_or_else(|| {
+            displays.push(active);
+            displays.len() - 1
+        });
+        Self { displays, active }
+    }
+
+    /// Windows with zero-area bounds (a platform that could not report
+    /// geometry) are always kept, so the grid is never emptied by the filter.
+    pub(crate) fn keeps(&self, window: (f32, f32, f32, f32)) -> bool {
+        let (_, _, width, height) = window;
+        if width <= 0.0 || height <= 0.0 {
+            return true;
+        }
+        monitor_for_bounds(window, &self.displays) == Some(self.active)
+    }
+}
+
+/// First id in `mru` (most recent first) whose window, looked up in
+/// `windows` by id, lies on `monitors[monitor]`. Ids with no known bounds are skipped.
+pub(crate) fn most_recent_on_monitor(
+    mru: &[u32],
+    windows: &[(u32, (f32, f32, f32, f32))],
+    monitors: &[(f32, f32, f32, f32)],
+    monitor: usize,
+) -> Option<u32> {
+    mru.iter().copied().find(|id| {
+        windows
+            .iter()
+            .find(|(wid, _)| wid == id)
+            .is_some_and(|(_, bounds)| monitor_for_bounds(*bounds, monitors) == Some(monitor))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: (f32, f32, f32, f32) = (0.0, 0.0, 2560.0, 1440.0);
+    const RIGHT: (f32, f32, f32, f32) = (2560.0, 1000.0, 1920.0, 1080.0);
+
+    #[test]
+    fn window_inside_one_monitor() {
+        assert_eq!(monitor_for_bounds((100.0, 100.0, 800.0, 600.0), &[LEFT, RIGHT]), Some(0));
+        assert_eq!(monitor_for_bounds((3000.0, 1200.0, 800.0, 600.0), &[LEFT, RIGHT]), Some(1));
+    }
+
+    #[test]
+    fn straddling_window_goes_to_larger_overlap_not_center() {
+        // Center (2600, 1100) lies on RIGHT, but most of the window is on LEFT.
+        let window = (1800.0, 700.0, 1600.0, 800.0);
+        assert_eq!(monitor_for_bounds(window, &[LEFT, RIGHT]), Some(0));
+    }
+
+    #[test]
+    fn straddling_window_with_center_off_every_monitor() {
+        // Center (2000, 500) is past LEFT's right edge and above RIGHT.
+        let monitors = [(0.0, 0.0, 1920.0, 1080.0), (1920.0, 0.0, 1280.0, 300.0)];
+        assert_eq!(monitor_for_bounds((1500.0, 0.0, 1000.0, 1000.0), &monitors), Some(0));
+    }
+
+    #[test]
+    fn window_off_every_monitor() {
+        assert_eq!(monitor_for_bounds((-900.0, 0.0, 800.0, 600.0), &[LEFT, RIGHT]), None);
+        assert_eq!(monitor_for_bounds((0.0, 0.0, 800.0, 600.0), &[]), None);
+    }
+
+    #[test]
+    fn filter_keeps_window_centered_in_gap_between_displays() {
+        // Displays with a 200px gap; the window's center (2700, 600) is in it.
+        let left = (0.0, 0.0, 2560.0, 1440.0);
+        let right = (2760.0, 0.0, 1920.0, 1080.0);
+        let window = (1900.0, 100.0, 1600.0, 1000.0);
+        assert!(MonitorFilter::new(vec![left, right], right).keeps(window));
+        assert!(!MonitorFilter::new(vec![left, right], left).keeps(window));
+    }
+
+    #[test]
+    fn filter_adds_active_display_missing_from_list() {
+        let filter = MonitorFilter::new(vec![LEFT], RIGHT);
+        assert!(filter.keeps((3000.0, 1200.0, 800.0, 600.0)));
+        assert!(!filter.keeps((100.0, 100.0, 800.0, 600.0)));
+    }
+
+    #[test]
+    fn filter_keeps_windows_without_geometry() {
+        let filter = MonitorFilter::new(vec![LEFT, RIGHT], RIGHT);
+        assert!(filter.keeps((0.0, 0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn most_recent_on_monitor_skips_other_monitors_and_unknown_ids() {
+        let windows = [
+            (1, (100.0, 100.0, 800.0, 600.0)),
+            (2, (3000.0, 1200.0, 800.0, 600.0)),
+            (3, (200.0, 200.0, 800.0, 600.0)),
+        ];
+        let mru = [9, 2, 3, 1];
+        assert_eq!(most_recent_on_monitor(&mru, &windows, &[LEFT, RIGHT], 0), Some(3));
+        assert_eq!(most_recent_on_monitor(&mru, &windows, &[LEFT, RIGHT], 1), Some(2));
+        assert_eq!(most_recent_on_monitor(&[1], &windows, &[LEFT, RIGHT], 1), None);
+    }
+}
@@ -1,5 +1,12 @@
 use gpui::*;
 
-pub(crate) fn open_keepalive(cx: &mut App) {
+pub(crate) fn open_keepalive(cx: &mut App, wanted: bool) {
+    if !wanted && !crate::platform::KEEPALIVE_REQUIRED {
+        eprintln!("[alt-tab] keepalive window disabled");
+        return;
+    }
+    if !wanted {
+        eprintln!("[alt-tab] use_keepalive_window=false ignored: this platform exits without a window");
+    }
     qol_plugin_api::keepalive::open_keepalive(cx, None);
 }
@@ -2,43 +2,120 @@ pub(crate) mod keepalive;
 pub(crate) mod run;
 
 use crate::app::{AltTabApp, PICKER_VISIBLE};
-use crate::config::{parse_hex_color, ActionMode, AltTabConfig, DisplayConfig};
-use crate::icon::build_icon_cache;
+use crate::config::{ActionMode, AltTabConfig, DisplayConfig, FilterSpec, PickerAnchor};
+use crate::icon::{build_icon_cache, windows_needing_icons};
 use crate::layout::*;
-use crate::monitor::MonitorTracker;
+use crate::monitor::{MonitorFilter, MonitorTracker};
 use crate::platform;
 use crate::platform::WindowInfo;
-use crate::preview::bgra_to_render_image;
+use crate::preview::{bgra_to_render_image, TimedPreviews};
 use gpui::*;
 use std::collections::{HashMap, HashSet};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 const DEFAULT_ESTIMATED_WINDOW_COUNT: usize = 8;
+const OPEN_WINDOW_ATTEMPTS: u32 = 2;
 
-pub(crate) fn default_estimated_window_count() -> usize {
-    DEFAULT_ESTIMATED_WINDOW_COUNT
+/// Set while `open_picker` runs. A Show dispatched from inside a platform call
+/// during window creation is coalesced into that open instead of making a
+/// second window before `current` holds the first.
+static OPENING: AtomicBool = AtomicBool::new(false);
+
+/// Holds `flag` for one `open_picker` call; released on drop.
+struct OpeningGuard(&'static AtomicBool);
+
+impl OpeningGuard {
+    fn acquire(flag: &'static AtomicBool) -> Option<Self> {
+        flag.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self(flag))
+    }
+}
+
+impl Drop for OpeningGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Monitor origin in whole pixels; identifies a monitor across snapshots.
+pub(crate) type MonitorKey = (i32, i32);
+
+/// Card counts the picker last displayed, per monitor, used to size a new
+/// picker before its list settles so it does not resize after the first frame.
+pub(crate) type WindowCounts = Arc<std::sync::Mutex<WindowCountMemory>>;
+
+#[derive(Default)]
+pub(crate) struct WindowCountMemory {
+    by_monitor: HashMap<MonitorKey, usize>,
+    last: Option<usize>,
+}
+
+impl WindowCountMemory {
+    fn remember(&mut self, key: Option<MonitorKey>, count: usize) {
+        if let Some(key) = key {
+            self.by_monitor.insert(key, count);
+        }
+        self.last = Some(count);
+    }
+
+    /// Count for `key`, else the last count displayed on any monitor.
+    fn estimate(&self, key: Option<MonitorKey>) -> usize {
+        key.and_then(|k| self.by_monitor.get(&k).copied())
+            .or(self.last)
+            .unwrap_or(DEFAULT_ESTIMATED_WINDOW_COUNT)
+    }
+}
+
+/// Key of the monitor the tracker currently considers active.
+pub(crate) fn active_monitor_key(tracker: &MonitorTracker) -> Option<MonitorKey> {
+    tracker.snapshot().map(|(m, _)| {
+        let origin = m.bounds().origin;
+        (origin.x.to_f64().round() as i32, origin.y.to_f64().round() as i32)
+    })
+}
+
+fn remembered_window_count(counts: &WindowCounts, key: Option<MonitorKey>) -> usize {
+    counts
+        .lock()
+        .map(|c| c.estimate(key))
+        .unwrap_or(DEFAULT_ESTIMATED_WINDOW_COUNT)
+}
+
+fn remember_window_count(counts: &WindowCounts, key: Option<MonitorKey>, count: usize) {
+    if let Ok(mut c) = counts.lock() {
+        c.remember(key, count);
+    }
 }
 
 pub(crate) fn open_picker(
     config: &AltTabConfig,
     current: &std::rc::Rc<std::cell::RefCell<Option<(WindowHandle<AltTabApp>, Point<Pixels>)>>>,
     tracker: &MonitorTracker,
-    last_window_count: Arc<AtomicUsize>,
+    window_counts: WindowCounts,
     window_cache: Arc<std::sync::Mutex<Vec<WindowInfo>>>,
-    preview_cache: Arc<std::sync::Mutex<HashMap<u32, Arc<RenderImage>>>>,
+    preview_cache: Arc<std::sync::Mutex<TimedPreviews>>,
     icon_cache: Arc<std::sync::Mutex<HashMap<String, Arc<RenderImage>>>>,
     reverse: bool,
+    group: Option<&FilterSpec>,
     cx: &mut App,
 ) {
     #[cfg(debug_assertions)]
     eprintln!("[alt-tab/open] show request (reverse={})", reverse);
 
-    // Reverse only cycles within an already-open picker — never opens one.
-    if reverse && current.borrow().is_none() {
+    // Reverse only cycles within an already-open picker unless reverse_can_open,
+    // in which case it opens one with the last window selected.
+    if reverse && current.borrow().is_none() && !config.reverse_can_open {
         return;
     }
 
+    let Some(_opening) = OpeningGuard::acquire(&OPENING) else {
+        #[cfg(debug_assertions)]
+        eprintln!("[alt-tab/open] picker already opening — coalescing show");
+        return;
+    };
+
     // Fast path: if picker is already visible and alt poll is running, just cycle.
     // This MUST run before get_open_windows() to avoid expensive AX/CG/proc_pidinfo
     // calls on every cycle keystroke.
@@ -74,12 +151,63 @@ pub(crate) fn open_picker(
         }
     }
 
+    let select_last = reverse && config.reverse_can_open;
+
+    // Checked before the picker takes focus; afterwards the picker is frontmost.
+    let modal_active = platform::frontmost_has_modal();
+    let active_monitor = if config.activate_to_active_monitor || config.display.current_monitor_only {
+        tracker.snapshot().map(|(m, _)| {
+            let b = m.bounds();
+            (
+                b.origin.x.to_f64() as f32,
+                b.origin.y.to_f64() as f32,
+                b.size.width.to_f64() as f32,
+                b.size.height.to_f64() as f32,
+            )
+        })
+    } else {
+        None
+    };
+    let activation_monitor = active_monitor.filter(|_| config.activate_to_active_monitor);
+
+    // Always use fast on-screen-only CG query for correct Z-order.
+    // Minimized windows come from the prewarm cache (avoids expensive
+    // proc_pidinfo / AX calls that made every open take 600-1300ms).
+    let mut on_screen = platform::get_on_screen_windows(config.display.include_desktop);
+    let front_id = on_screen.iter().find(|w| !w.is_minimized).map(|w| w.id);
+    if let Some(id) = front_id {
+        crate::focus_history::note_frontmost(id);
+    }
+    // With no focused window to drop, every window stays listed.
+    let excluded_window = front_id.filter(|_| config.exclude_current_window);
+    on_screen.retain(|w| Some(w.id) != excluded_window);
+    let group_filter = group.cloned();
+    // Without a resolvable monitor every window stays listed.
+    let monitor_filter = active_monitor.filter(|_| config.display.current_monitor_only).map(|m| {
+        let displays = cx
+            .displays()
+            .iter()
+            .map(|d| {
+                let b = d.bounds();
+                (
+                    b.origin.x.to_f64() as f32,
+                    b.origin.y.to_f64() as f32,
+                    b.size.width.to_f64() as f32,
+                    b.size.height.to_f64() as f32,
+                )
+            })
+            .collect();
+        MonitorFilter::new(displays, m)
+    });
+    let keep = |w: &WindowInfo| {
+        group.map_or(true, |g| g.matches(&w.app_name, &w.title))
+            && monitor_filter
+                .as_ref()
+                .map_or(true, |f| f.keeps((w.x, w.y, w.width, w.height)))
+    };
+    on_screen.retain(|w| keep(w));
     let display_windows: Vec<WindowInfo> = {
-        // Always use fast on-screen-only CG query for correct Z-order.
-        // Minimized windows come from the prewarm cache (avoids expensive
-        // proc_pidinfo / AX calls that made every open take 600-1300ms).
-        let on_screen = platform::get_on_screen_windows();
-        if config.display.show_minimized {
+        let listed: Vec<WindowInfo> = if config.display.show_minimized {
             let on_screen_ids: HashSet<u32> = on_screen.iter().map(|w| w.id).collect();
             let cached_minimized: Vec<WindowInfo> = window_cache
                 .lock()
@@ -87,7 +215,7 @@ pub(crate) fn open_picker(
                 .map(|cache| {
                     cache
                         .iter()
-                        .filter(|w| w.is_minimized && !on_screen_ids.contains(&w.id))
+                        .filter(|w| w.is_minimized && !on_screen_ids.contains(&w.id) && keep(w))
                         .cloned()
                         .collect()
                 })
@@ -97,36 +225,58 @@ pub(crate) fn open_picker(
             all
         } else {
             on_screen.into_iter().filter(|w| !w.is_minimized).collect()
-        }
+        };
+        // Same order the delegate will use, so indices below (likely selection,
+        // initial capture) line up with the cards.
+        crate::delegate::ordering::arrange(
+            listed,
+            config.display.order,
+            config.minimized_sort,
+            &crate::focus_history::snapshot(),
+        )
     };
 
-    // Grab pre-warmed previews from cache (instant). Only capture missing windows.
-    let mut initial_previews: HashMap<u32, Arc<RenderImage>> = HashMap::new();
-    let mut missing_targets: Vec<(usize, u32)> = Vec::new();
-    if let Ok(pcache) = preview_cache.lock() {
-        for (i, win) in display_windows.iter().enumerate() {
-            if let Some(img) = pcache.get(&win.id) {
-                initial_previews.insert(win.id, img.clone());
-            } else {
-                missing_targets.push((i, win.id));
-            }
-        }
+    // restore_last: the window last activated from the picker, if it is still listed.
+    let restored_window = (config.open_behavior == crate::config::OpenBehavior::RestoreLast)
+        .then(crate::focus_history::last_activated)
+        .flatten()
+        .filter(|id| display_windows.iter().any(|w| w.id == *id));
+
+    // Grab pre-warmed previews from cache (instant). Only the window the user is
+    // most likely to commit to is captured synchronously; the live preview loop
+    // runs immediately once the picker is visible and fills in everything else.
+    let mut initial_previews: TimedPreviews = preview_cache
+        .lock()
+        .map(|pcache| {
+            display_windows
+                .iter()
+                .filter_map(|w| pcache.get(&w.id).map(|entry| (w.id, entry.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let likely_selected = if select_last {
+        display_windows.len().checked_sub(1)
+    } else if let Some(id) = restored_window {
+        display_windows.iter().position(|w| w.id == id)
     } else {
-        missing_targets = display_windows
-            .iter()
-            .enumerate()
-            .map(|(i, w)| (i, w.id))
-            .collect();
-    }
-    // Synchronous CG capture only for windows not yet in the prewarm cache
-    if !missing_targets.is_empty() {
-        for (idx, rgba_opt) in
-            platform::capture_previews_cg(&missing_targets, PREVIEW_MAX_WIDTH, PREVIEW_MAX_HEIGHT)
-        {
-            let Some(rgba) = rgba_opt else { continue };
-            let Some(win) = display_windows.get(idx) else { continue };
+        likely_selected_index(config, existing.as_ref().map(|(h, _)| h), display_windows.len(), cx)
+    };
+    if let Some(win) = likely_selected
+        .and_then(|ix| display_windows.get(ix))
+        .filter(|w| !w.is_minimized)
+    {
+        let (max_w, max_h) = preview_max_size(config.display.card_scale());
+        let captured = platform::capture_previews_cg(
+            &[(0, win.id)],
+            max_w,
+            max_h,
+            config.display.preview_quality,
+            config.display.capture_cursor,
+            config.display.screen_capture_kit,
+        );
+        if let Some((_, Some(rgba))) = captured.into_iter().next() {
             if let Some(img) = bgra_to_render_image(&rgba.data, rgba.width, rgba.height) {
-                initial_previews.insert(win.id, img);
+                initial_previews.insert(win.id, (img, std::time::Instant::now()));
             }
         }
     }
@@ -138,17 +288,23 @@ pub(crate) fn open_picker(
 
     // Reuse existing picker window if possible (reopen after dismiss).
     if let Some((handle, created_on_origin)) = existing {
-        let target_count = display_windows.len().max(1);
+        let target_count = card_count(config, &display_windows).max(1);
+        remember_window_count(&window_counts, active_monitor_key(tracker), target_count);
         let target_monitor = tracker.snapshot().map(|(m, _)| m);
         let monitor_size = target_monitor.as_ref().map(|m| m.size());
         let (target_w, target_h) =
-            picker_dimensions(target_count, config.display.max_columns, monitor_size, config.display.show_hotkey_hints);
+            picker_dimensions(target_count, config.display.max_columns, monitor_size, Chrome::from_display(&config.display), config.display.layout, config.display.card_scale());
         let target_size = size(px(target_w), px(target_h));
         let target_bounds = if let Some(ref active) = target_monitor {
             active.centered_bounds(target_size)
         } else {
             Bounds::centered(None, target_size, cx)
         };
+        let target_bounds = anchored_bounds(
+            &config.display,
+            target_bounds,
+            target_monitor.as_ref().map(|m| m.bounds()),
+        );
 
         // Determine if the target monitor differs from the one the window was created on.
         let target_origin = target_monitor
@@ -172,10 +328,19 @@ pub(crate) fn open_picker(
                     monitor_changed,
                 );
 
-                if monitor_changed {
+                // Cursor anchoring and fixed offsets can move the picker on the same monitor.
+                let repositions = config.display.picker_anchor != PickerAnchor::Center
+                    || !config.display.picker_offsets.is_empty();
+                let anchor_moved = repositions && {
+                    let current = window.window_bounds().get_bounds().origin;
+                    let dx = (current.x.to_f64() - target_bounds.origin.x.to_f64()).abs();
+                    let dy = (current.y.to_f64() - target_bounds.origin.y.to_f64()).abs();
+                    dx > MONITOR_TOLERANCE_PX || dy > MONITOR_TOLERANCE_PX
+                };
+                if monitor_changed || anchor_moved {
                     let x = target_bounds.origin.x.to_f64() as i32;
                     let y = target_bounds.origin.y.to_f64() as i32;
-                    if !platform::move_app_window("qol-alt-tab-picker", x, y) {
+                    if !platform::move_app_window(platform::PICKER_WINDOW_TITLE, x, y) {
                         return false;
                     }
                 }
@@ -187,16 +352,35 @@ pub(crate) fn open_picker(
                 );
 
                 view.action_mode = config.action_mode.clone();
+                view.action_modifier = config.action_modifier;
                 view.alt_was_held = true;
+                view.peeking = false;
+                view.hint_prefix.clear();
+                view.layout_mode = config.display.layout;
+                view.max_columns = config.display.max_columns;
+                view.modal_warning = modal_active;
+                view.close_armed = None;
+                view.grid_scroll.set_offset(point(px(0.0), px(0.0)));
+                view.scrolled_selection = None;
+                view.hovered_card = None;
+                view.close_fade_ms = crate::app::fade::configured_ms(config);
+                view.auto_dismiss_ms = config.display.auto_dismiss_ms;
+                view.activation_debounce_ms = config.activation_debounce_ms;
+                view.min_visible_ms = config.min_visible_ms;
+                view.empty_enter_action = config.empty_enter_action.clone();
+                view.cancel_dismiss();
+                if view.pinned_on_top != config.pin_on_top() {
+                    view.pinned_on_top = config.pin_on_top();
+                    platform::set_window_always_on_top(platform::PICKER_WINDOW_TITLE, view.pinned_on_top);
+                }
 
-                let (card_color, card_opacity) = resolve_card_bg(&config.display);
                 view.delegate.update(cx, |s, _cx| {
-                    s.label_config = config.label.clone();
-                    s.transparent_background = config.display.transparent_background;
-                    s.card_bg_color = card_color;
-                    s.card_bg_opacity = card_opacity;
-                    s.show_debug_overlay = config.display.show_debug_overlay;
-                    s.show_hotkey_hints = config.display.show_hotkey_hints;
+                    s.apply_config(config);
+                    s.activation_monitor = activation_monitor;
+                    s.excluded_window = excluded_window;
+                    s.current_window_id = front_id;
+                    s.group_filter = group_filter.clone();
+                    s.monitor_filter = monitor_filter.clone();
                 });
 
                 if config.action_mode == ActionMode::HoldToSwitch {
@@ -205,6 +389,7 @@ pub(crate) fn open_picker(
                 } else {
                     view._alt_poll_task = None;
                 }
+                view.restart_idle_timer(window, cx);
 
                 view.apply_cached_windows(
                     display_windows.clone(),
@@ -215,12 +400,17 @@ pub(crate) fn open_picker(
                 );
 
                 // Mirror the CycleOnce behavior from AltTabApp::new()
-                if config.open_behavior == crate::config::OpenBehavior::CycleOnce
+                if config.cycles_on_open()
                     && config.reset_selection_on_open
                     && display_windows.len() >= 2
                 {
                     view.delegate.update(cx, |s, _cx| s.select_next());
                 }
+                if select_last {
+                    view.delegate.update(cx, |s, _cx| s.select_last());
+                } else if let Some(id) = restored_window {
+                    view.delegate.update(cx, |s, _cx| s.select_window_id(id));
+                }
 
                 let current_bounds = window.window_bounds().get_bounds();
                 let current_size = current_bounds.size;
@@ -249,23 +439,14 @@ pub(crate) fn open_picker(
             if !initial_previews.is_empty() {
                 let _ = handle.update(cx, |view, _window, cx| {
                     view.delegate.update(cx, |state, cx| {
-                        for (wid, img) in initial_previews {
-                            state.live_previews.insert(wid, img);
-                        }
+                        state.insert_previews(initial_previews);
                         cx.notify();
                     });
                 });
             }
             // Async-fill missing icons for reuse path too
-            let missing_apps: Vec<String> = display_windows
-                .iter()
-                .map(|w| w.app_name.clone())
-                .filter(|name| !icons.contains_key(name))
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-            if !missing_apps.is_empty() {
-                let windows_for_icons = display_windows;
+            let windows_for_icons = windows_needing_icons(&display_windows, &icons);
+            if !windows_for_icons.is_empty() {
                 let icon_cache_for_fill = icon_cache;
                 let handle_for_fill = handle;
                 cx.spawn(async move |cx: &mut AsyncApp| {
@@ -285,9 +466,7 @@ pub(crate) fn open_picker(
                     let _ = cx.update(|cx| {
                         let _ = handle_for_fill.update(cx, |view, _window, cx| {
                             view.delegate.update(cx, |state, cx| {
-                                for (name, img) in rendered {
-                                    state.icon_cache.insert(name, img);
-                                }
+                                state.merge_icons(rendered);
                                 cx.notify();
                             });
                         });
@@ -309,20 +488,29 @@ pub(crate) fn open_picker(
         *current.borrow_mut() = None;
     }
 
-    let target_count = display_windows.len().max(1);
-    let estimated_count = target_count
-        .max(last_window_count.load(Ordering::Relaxed))
-        .max(1);
+    let target_count = card_count(config, &display_windows).max(1);
+    let monitor_key = active_monitor_key(tracker);
+    let estimated_count = if config.collapse_apps {
+        target_count
+    } else {
+        target_count.max(remembered_window_count(&window_counts, monitor_key))
+    };
+    remember_window_count(&window_counts, monitor_key, target_count);
     let create_monitor = tracker.snapshot().map(|(m, _)| m);
     let monitor_size = create_monitor.as_ref().map(|m| m.size());
     let (win_w, win_h) =
-        picker_dimensions(estimated_count, config.display.max_columns, monitor_size, config.display.show_hotkey_hints);
+        picker_dimensions(estimated_count, config.display.max_columns, monitor_size, Chrome::from_display(&config.display), config.display.layout, config.display.card_scale());
     let win_size = size(px(win_w), px(win_h));
     let bounds = if let Some(ref active) = create_monitor {
         active.centered_bounds(win_size)
     } else {
         Bounds::centered(None, win_size, cx)
     };
+    let bounds = anchored_bounds(
+        &config.display,
+        bounds,
+        create_monitor.as_ref().map(|m| m.bounds()),
+    );
     let create_origin = create_monitor
         .as_ref()
         .map(|m| m.bounds().origin)
@@ -340,15 +528,7 @@ pub(crate) fn open_picker(
         bounds.origin, bounds.size
     );
 
-    let action_mode_for_init = config.action_mode.clone();
-    let display_windows_for_init = display_windows.clone();
-    let config_for_init = config.clone();
-    let cycle_on_open = config.open_behavior == crate::config::OpenBehavior::CycleOnce;
-    let icons_for_init = icons.clone();
     let transparent_bg = config.display.transparent_background;
-    let show_debug_overlay = config.display.show_debug_overlay;
-    let show_hotkey_hints = config.display.show_hotkey_hints;
-    let (card_color_init, card_opacity_init) = resolve_card_bg(&config.display);
 
     let window_background = if transparent_bg {
         WindowBackgroundAppearance::Transparent
@@ -356,52 +536,74 @@ pub(crate) fn open_picker(
         WindowBackgroundAppearance::Opaque
     };
 
-    let handle = cx.open_window(
-        WindowOptions {
-            window_bounds: Some(WindowBounds::Windowed(bounds)),
-            titlebar: None,
-            window_decorations: Some(if transparent_bg { WindowDecorations::Server } else { WindowDecorations::Client }),
-            kind: platform::picker_window_kind(),
-            focus: true,
-            window_background: window_background,
-            ..Default::default()
-        },
-        move |window, cx| {
-            window.set_window_title("qol-alt-tab-picker");
-            let label_config = config_for_init.label.clone();
-            let transparent_background = config_for_init.display.transparent_background;
-            let view = cx.new(|cx| {
-                AltTabApp::new(
-                    window,
-                    cx,
-                    action_mode_for_init,
-                    display_windows_for_init,
-                    label_config,
-                    transparent_background,
-                    card_color_init,
-                    card_opacity_init,
-                    show_debug_overlay,
-                    show_hotkey_hints,
-                    cycle_on_open,
-                    initial_previews,
-                    icons_for_init,
-                )
-            });
-            window.focus(&view.focus_handle(cx));
-            window.activate_window();
-            view
-        },
-    );
-    let opened_handle = if let Ok(h) = handle {
-        #[cfg(debug_assertions)]
-        eprintln!("[alt-tab/open] opened new picker window");
-        *current.borrow_mut() = Some((h.clone(), create_origin));
-        Some(h)
-    } else {
-        #[cfg(debug_assertions)]
-        eprintln!("[alt-tab/open] failed to open picker window");
-        None
-    };
+    // Window creation can fail transiently (e.g. compositor hiccup); retry once
+    // before giving up so the daemon never believes a missing picker is open.
+    let mut opened_handle = None;
+    for attempt in 1..=OPEN_WINDOW_ATTEMPTS {
+        let display_windows_for_init = display_windows.clone();
+        let config_for_init = config.clone();
+        let icons_for_init = icons.clone();
+        let previews_for_init = initial_previews.clone();
+        let group_for_init = group_filter.clone();
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                titlebar: None,
+                window_decorations: Some(if transparent_bg { WindowDecorations::Server } else { WindowDecorations::Client }),
+                kind: platform::picker_window_kind(),
+                focus: true,
+                window_background: window_background,
+                app_id: Some(platform::PICKER_APP_ID.to_string()),
+                ..Default::default()
+            },
+            move |window, cx| {
+                window.set_window_title(platform::PICKER_WINDOW_TITLE);
+                let view = cx.new(|cx| {
+                    let mut app = AltTabApp::new(
+                        window,
+                        cx,
+                        &config_for_init,
+                        display_windows_for_init,
+                        previews_for_init,
+                        icons_for_init,
+                    );
+                    app.modal_warning = modal_active;
+                    app.delegate.update(cx, |s, _cx| {
+                        s.activation_monitor = activation_monitor;
+                        s.excluded_window = excluded_window;
+                        s.current_window_id = front_id;
+                        s.group_filter = group_for_init.clone();
+                        s.monitor_filter = monitor_filter.clone();
+                        if select_last {
+                            s.select_last();
+                        } else if let Some(id) = restored_window {
+                            s.select_window_id(id);
+                        }
+                    });
+                    app
+                });
+                window.focus(&view.focus_handle(cx));
+                window.activate_window();
+                view
+            },
+        );
+        match handle {
+            Ok(h) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[alt-tab/open] opened new picker window");
+                *current.borrow_mut() = Some((h.clone(), create_origin));
+                opened_handle = Some(h);
+                break;
+            }
+            Err(e) => {
+                eprintln!(
+                    "[alt-tab] failed to open picker window (attempt {}/{}): {}",
+                    attempt, OPEN_WINDOW_ATTEMPTS, e
+                );
+                *current.borrow_mut() = None;
+            }
+        }
+    }
     if opened_handle.is_some() {
         PICKER_VISIBLE.store(true, Ordering::Relaxed);
         cx.activate(true);
@@ -412,19 +614,14 @@ pub(crate) fn open_picker(
     if transparent_bg {
         platform::disable_window_shadow();
     }
+    if opened_handle.is_some() && config.pin_on_top() {
+        platform::set_window_always_on_top(platform::PICKER_WINDOW_TITLE, true);
+    }
 
-    // Spawn background icon fetch for any apps not yet in the cache.
-    // This fills icons within ~50ms instead of waiting for the next prewarm cycle.
+    // Icons are fetched lazily: only apps shown now that have none cached yet.
     if let Some(wh) = opened_handle {
-        let missing_apps: Vec<String> = display_windows
-            .iter()
-            .map(|w| w.app_name.clone())
-            .filter(|name| !icons.contains_key(name))
-            .collect::<std::collections::HashSet<_>>()
-            .into_iter()
-            .collect();
-        if !missing_apps.is_empty() {
-            let windows_for_icons = display_windows;
+        let windows_for_icons = windows_needing_icons(&display_windows, &icons);
+        if !windows_for_icons.is_empty() {
             let icon_cache_for_fill = icon_cache;
             cx.spawn(async move |cx: &mut AsyncApp| {
                 let executor = cx.background_executor().clone();
@@ -443,9 +640,7 @@ pub(crate) fn open_picker(
                 let _ = cx.update(|cx| {
                     let _ = wh.update(cx, |view, _window, cx| {
                         view.delegate.update(cx, |state, cx| {
-                            for (name, img) in rendered {
-                                state.icon_cache.insert(name, img);
-                            }
+                            state.merge_icons(rendered);
                             cx.notify();
                         });
                     });
@@ -459,11 +654,61 @@ pub(crate) fn open_picker(
     set_macos_accessory_policy();
 }
 
-fn resolve_card_bg(display: &DisplayConfig) -> (u32, f32) {
-    let (r, g, b) = parse_hex_color(&display.card_background_color).unwrap_or((0x1a, 0x1e, 0x2a));
-    let color = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
-    let opacity = display.card_background_opacity.clamp(0.0, 1.0);
-    (color, opacity)
+/// Index the picker will select once it opens, mirroring the selection logic of
+/// `AltTabApp::new` and the reuse path.
+fn likely_selected_index(
+    config: &AltTabConfig,
+    existing: Option<&WindowHandle<AltTabApp>>,
+    count: usize,
+    cx: &mut App,
+) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let kept = existing
+        .filter(|_| !config.reset_selection_on_open)
+        .and_then(|handle| {
+            handle
+                .update(cx, |view, _window, cx| view.delegate.read(cx).selected_index)
+                .ok()
+                .flatten()
+        });
+    let cycles = config.cycles_on_open() && count >= 2;
+    let ix = kept.unwrap_or(if cycles { 1 } else { 0 });
+    Some(ix.min(count - 1))
+}
+
+/// Re-position `centered` according to the configured offset or anchor,
+/// keeping it inside `monitor`.
+fn anchored_bounds(
+    display: &DisplayConfig,
+    centered: Bounds<Pixels>,
+    monitor: Option<Bounds<Pixels>>,
+) -> Bounds<Pixels> {
+    let Some(area) = monitor else {
+        return centered;
+    };
+    let min_x = area.origin.x.to_f64() as f32;
+    let min_y = area.origin.y.to_f64() as f32;
+    let (x, y) = if let Some(offset) = display.picker_offset_for((min_x, min_y)) {
+        (min_x + offset.x, min_y + offset.y)
+    } else if display.picker_anchor == PickerAnchor::CursorCentered {
+        let Some((cursor_x, cursor_y)) = platform::cursor_position() else {
+            return centered;
+        };
+        let w = centered.size.width.to_f64() as f32;
+        let h = centered.size.height.to_f64() as f32;
+        (cursor_x - w / 2.0, cursor_y - h / 2.0)
+    } else {
+        return centered;
+    };
+    let max_x = min_x + (area.size.width - centered.size.width).to_f64() as f32;
+    let max_y = min_y + (area.size.height - centered.size.height).to_f64() as f32;
+    // min() before max() so an oversized picker pins to the monitor's top-left.
+    Bounds {
+        origin: point(px(x.min(max_x).max(min_x)), px(y.min(max_y).max(min_y))),
+        size: centered.size,
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -475,3 +720,39 @@ pub(crate) fn set_macos_accessory_policy() {
     let app = NSApplication::sharedApplication(mtm);
     app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
 }
+
+/// Cards the picker will show: one per window, or one per app when collapsed.
+fn card_count(config: &AltTabConfig, windows: &[WindowInfo]) -> usize {
+    if config.collapse_apps {
+        windows.iter().map(|w| w.app_name.as_str()).collect::<HashSet<_>>().len()
+    } else {
+        windows.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_open_while_opening_is_coalesced() {
+        static FLAG: AtomicBool = AtomicBool::new(false);
+        let first = OpeningGuard::acquire(&FLAG);
+        assert!(first.is_some());
+        assert!(OpeningGuard::acquire(&FLAG).is_none());
+        drop(first);
+        assert!(OpeningGuard::acquire(&FLAG).is_some());
+    }
+
+    #[test]
+    fn unseen_monitor_falls_back_to_last_displayed_count() {
+        let mut counts = WindowCountMemory::default();
+        assert_eq!(counts.estimate(Some((0, 0))), DEFAULT_ESTIMATED_WINDOW_COUNT);
+        counts.remember(Some((0, 0)), 3);
+        counts.remember(Some((1920, 0)), 12);
+        assert_eq!(counts.estimate(Some((0, 0))), 3);
+        assert_eq!(counts.estimate(Some((1920, 0))), 12);
+        assert_eq!(counts.estimate(Some((3840, 0))), 12);
+        assert_eq!(counts.estimate(None), 12);
+    }
+}
@@ -1,21 +1,26 @@
 use super::keepalive::open_keepalive;
-use super::open_picker;
+use super::{open_picker, WindowCounts};
 use crate::app::{AltTabApp, PICKER_VISIBLE};
-use crate::config::AltTabConfig;
+use crate::config::{AltTabConfig, FilterSpec, MinimizedActivate, Modifier};
 use crate::daemon;
-use crate::icon::build_icon_cache;
-use crate::layout::{PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH};
-use crate::monitor::MonitorTracker;
+use crate::layout::preview_max_size;
+use crate::monitor::{most_recent_on_monitor, MonitorTracker};
 use crate::platform;
 use crate::platform::WindowInfo;
-use crate::preview::bgra_to_render_image;
+use crate::preview::{bgra_to_render_image, over_budget, render_image_bytes, TimedPreviews};
 use gpui::*;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::Ordering;
 use std::sync::{mpsc, Arc, Mutex};
 use std::time::Duration;
 
 const PREWARM_REFRESH_INTERVAL_MS: u64 = 1200;
+/// Check interval while the platform reports window list changes as events.
+const WATCHED_REFRESH_INTERVAL_MS: u64 = 200;
+/// Full refresh at least this often even without events (title changes etc.).
+const FORCED_REFRESH_INTERVAL_MS: u64 = 10_000;
+/// Modifier poll interval while deciding between a tap and a hold.
+const TAP_POLL_INTERVAL_MS: u64 = 15;
 
 pub(crate) fn run_app(
     config: AltTabConfig,
@@ -27,7 +32,13 @@ pub(crate) fn run_app(
     app.run(move |cx: &mut App| {
         let tracker = MonitorTracker::start(cx);
 
-        open_keepalive(cx);
+        open_keepalive(cx, config.use_keepalive_window);
+        crate::preview::clear_spilled();
+        // Decode (and report a bad file) now rather than on the first open.
+        crate::preview::placeholder_image(config.display.placeholder_image_path());
+        if let Some(path) = config.daemon.event_log_path() {
+            crate::events::start(&path);
+        }
 
         #[cfg(target_os = "macos")]
         super::set_macos_accessory_policy();
@@ -35,21 +46,20 @@ pub(crate) fn run_app(
         let current: std::rc::Rc<
             std::cell::RefCell<Option<(WindowHandle<AltTabApp>, Point<Pixels>)>>,
         > = std::rc::Rc::new(std::cell::RefCell::new(None));
-        let last_window_count =
-            Arc::new(AtomicUsize::new(super::default_estimated_window_count()));
+        let window_counts: WindowCounts = Arc::default();
         let window_cache: Arc<Mutex<Vec<WindowInfo>>> = Arc::new(Mutex::new(Vec::new()));
-        let preview_cache: Arc<Mutex<HashMap<u32, Arc<RenderImage>>>> =
+        let preview_cache: Arc<Mutex<TimedPreviews>> =
             Arc::new(Mutex::new(HashMap::new()));
         let icon_cache: Arc<Mutex<HashMap<String, Arc<RenderImage>>>> =
             Arc::new(Mutex::new(HashMap::new()));
 
         // Prewarm: poll window list + capture CG previews + icons when picker is hidden
         let warm_cache = window_cache.clone();
-        let warm_count = last_window_count.clone();
         let warm_previews = preview_cache.clone();
         let warm_icons = icon_cache.clone();
         cx.spawn(async move |cx: &mut AsyncApp| {
             let executor = cx.background_executor().clone();
+            let mut last_refresh: Option<std::time::Instant> = None;
             loop {
                 if PICKER_VISIBLE.load(Ordering::Relaxed) {
                     executor
@@ -57,21 +67,48 @@ pub(crate) fn run_app(
                         .await;
                     continue;
                 }
+                // With an event subscription, skip enumeration while nothing changed.
+                let changed = executor.spawn(async { platform::window_list_changed() }).await;
+                let refresh_due = last_refresh.map_or(true, |t| {
+                    t.elapsed() >= Duration::from_millis(FORCED_REFRESH_INTERVAL_MS)
+                });
+                if changed == Some(false) && !refresh_due {
+                    executor
+                        .timer(Duration::from_millis(WATCHED_REFRESH_INTERVAL_MS))
+                        .await;
+                    continue;
+                }
+                last_refresh = Some(std::time::Instant::now());
+                // Reloaded every pass so profile switches and config edits
+                // reach enumeration and capture without a restart.
+                let warm_display = crate::config::load_alt_tab_config().display;
+                let (other_spaces, desktop) =
+                    (warm_display.include_other_spaces, warm_display.include_desktop);
                 let windows = executor
-                    .spawn(async { platform::get_open_windows() })
+                    .spawn(async move { platform::get_open_windows(other_spaces, desktop) })
                     .await;
-                warm_count.store(windows.len().max(1), Ordering::Relaxed);
+                if let Some(front) = windows.iter().find(|w| !w.is_minimized) {
+                    crate::focus_history::note_frontmost(front.id);
+                }
+                crate::focus_history::retain(&windows.iter().map(|w| w.id).collect());
 
-                // Capture CG previews in background so open_picker can grab them instantly
-                let targets: Vec<(usize, u32)> =
-                    windows.iter().enumerate().map(|(i, w)| (i, w.id)).collect();
+                // Capture CG previews in background so open_picker can grab them instantly.
+                // With preview_selected_only, open_picker captures the selected window itself.
+                let targets: Vec<(usize, u32)> = windows
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, w)| {
+                        !warm_display.preview_selected_only || warm_display.always_previews(&w.app_name)
+                    })
+                    .map(|(i, w)| (i, w.id))
+                    .collect();
+                let (max_w, max_h) = preview_max_size(warm_display.card_scale());
+                let quality = warm_display.preview_quality;
+                let cursor = warm_display.capture_cursor;
+                let sck = warm_display.screen_capture_kit;
                 let captured = executor
                     .spawn(async move {
-                        platform::capture_previews_cg(
-                            &targets,
-                            PREVIEW_MAX_WIDTH,
-                            PREVIEW_MAX_HEIGHT,
-                        )
+                        platform::capture_previews_cg(&targets, max_w, max_h, quality, cursor, sck)
                     })
                     .await;
                 if let Ok(mut pcache) = warm_previews.lock() {
@@ -86,30 +123,39 @@ pub(crate) fn run_app(
                         if let Some(img) =
                             bgra_to_render_image(&rgba.data, rgba.width, rgba.height)
                         {
-                            pcache.insert(win.id, img);
+                            pcache.insert(win.id, (img, std::time::Instant::now()));
+                        }
+                    }
+                    // Keep the budget's worth of previews, frontmost windows first.
+                    if let Some(budget) = warm_display.preview_memory_budget() {
+                        let entries: Vec<(u32, usize)> = windows
+                            .iter()
+                            .filter_map(|w| Some((w.id, render_image_bytes(&pcache.get(&w.id)?.0))))
+                            .collect();
+                        for id in over_budget(&entries, budget) {
+                            pcache.remove(&id);
                         }
                     }
                 }
 
-                // Extract app icons in background
-                let icon_windows = windows.clone();
-                let raw_icons = executor
-                    .spawn(async move { platform::get_app_icons(&icon_windows) })
-                    .await;
-                if !raw_icons.is_empty() {
-                    let rendered = build_icon_cache(raw_icons);
-                    if let Ok(mut icache) = warm_icons.lock() {
-                        *icache = rendered;
-                    }
+                // Icons are fetched lazily when the picker first shows an app;
+                // here only apps that are gone are dropped.
+                if let Ok(mut icache) = warm_icons.lock() {
+                    let live_apps: std::collections::HashSet<&str> =
+                        windows.iter().map(|w| w.app_name.as_str()).collect();
+                    icache.retain(|name, _| live_apps.contains(name.as_str()));
                 }
 
                 if let Ok(mut cache) = warm_cache.lock() {
                     *cache = windows;
                 }
 
-                executor
-                    .timer(Duration::from_millis(PREWARM_REFRESH_INTERVAL_MS))
-                    .await;
+                let interval = if changed.is_some() {
+                    WATCHED_REFRESH_INTERVAL_MS
+                } else {
+                    PREWARM_REFRESH_INTERVAL_MS
+                };
+                executor.timer(Duration::from_millis(interval)).await;
             }
         })
         .detach();
@@ -119,11 +165,12 @@ pub(crate) fn run_app(
                 &config,
                 &current,
                 &tracker,
-                last_window_count.clone(),
+                window_counts.clone(),
                 window_cache.clone(),
                 preview_cache.clone(),
                 icon_cache.clone(),
                 false,
+                None,
                 cx,
             );
         }
@@ -131,12 +178,18 @@ pub(crate) fn run_app(
         // Poll the daemon channel for Show/Kill commands
         let rx = Arc::new(std::sync::Mutex::new(rx));
         let tracker_clone = tracker.clone();
+        // A command taken off the channel while draining, handled next.
+        let mut stashed: Option<daemon::Command> = None;
         cx.spawn(async move |cx: &mut AsyncApp| loop {
-            let rx2 = rx.clone();
-            let cmd = cx
-                .background_executor()
-                .spawn(async move { rx2.lock().ok()?.recv().ok() })
-                .await;
+            let cmd = match stashed.take() {
+                Some(cmd) => Some(cmd),
+                None => {
+                    let rx2 = rx.clone();
+                    cx.background_executor()
+                        .spawn(async move { rx2.lock().ok()?.recv().ok() })
+                        .await
+                }
+            };
 
             match cmd {
                 Some(daemon::Command::Show) | Some(daemon::Command::ShowReverse) => {
@@ -145,25 +198,156 @@ pub(crate) fn run_app(
                     eprintln!("[alt-tab/daemon] received Show (reverse={})", reverse);
                     let current2 = current.clone();
                     let tracker2 = tracker_clone.clone();
-                    let last_window_count2 = last_window_count.clone();
+                    let window_counts2 = window_counts.clone();
                     let window_cache2 = window_cache.clone();
                     let preview_cache2 = preview_cache.clone();
                     let icon_cache2 = icon_cache.clone();
+                    let reloaded_config = crate::config::load_alt_tab_config();
+                    if !reverse
+                        && !PICKER_VISIBLE.load(Ordering::Relaxed)
+                        && reloaded_config.discriminates_taps()
+                        && released_within(
+                            cx,
+                            reloaded_config.action_modifier,
+                            Duration::from_millis(reloaded_config.tap_max_ms),
+                        )
+                        .await
+                    {
+                        let include_desktop = reloaded_config.display.include_desktop;
+                        let skip_same_app = reloaded_config.flip_skips_same_app;
+                        cx.background_executor()
+                            .spawn(async move {
+                                switch_to_previous(include_desktop, skip_same_app)
+                            })
+                            .await;
+                        // Shows queued during the wait (Alt+Tab+Tab) belong to this tap;
+                        // replaying them would flip straight back.
+                        stashed = drain_shows(&rx);
+                        continue;
+                    }
                     let _ = cx.update(|app_cx| {
-                        let reloaded_config = crate::config::load_alt_tab_config();
                         open_picker(
                             &reloaded_config,
                             &current2,
                             &tracker2,
-                            last_window_count2,
+                            window_counts2,
                             window_cache2,
                             preview_cache2,
                             icon_cache2,
                             reverse,
+                            None,
+                            app_cx,
+                        );
+                    });
+                }
+                Some(daemon::Command::ShowGroup(name)) => {
+                    let reloaded_config = crate::config::load_alt_tab_config();
+                    let Some(group) = reloaded_config.smart_groups.get(&name) else {
+                        eprintln!("[alt-tab] unknown smart group '{}'", name);
+                        continue;
+                    };
+                    let current2 = current.clone();
+                    let tracker2 = tracker_clone.clone();
+                    let window_counts2 = window_counts.clone();
+                    let window_cache2 = window_cache.clone();
+                    let preview_cache2 = preview_cache.clone();
+                    let icon_cache2 = icon_cache.clone();
+                    let _ = cx.update(|app_cx| {
+                        open_picker(
+                            &reloaded_config,
+                            &current2,
+                            &tracker2,
+                            window_counts2,
+                            window_cache2,
+                            preview_cache2,
+                            icon_cache2,
+                            false,
+                            Some(group),
                             app_cx,
                         );
                     });
                 }
+                Some(daemon::Command::ShowAppOnly) => {
+                    let reloaded_config = crate::config::load_alt_tab_config();
+                    // Read before the picker takes focus; no focused app means a plain show.
+                    let focused_app = cx
+                        .background_executor()
+                        .spawn(async { platform::frontmost_app_name() })
+                        .await;
+                    let app_filter = focused_app.map(|app| FilterSpec::for_app(&app));
+                    let current2 = current.clone();
+                    let tracker2 = tracker_clone.clone();
+                    let window_counts2 = window_counts.clone();
+                    let window_cache2 = window_cache.clone();
+                    let preview_cache2 = preview_cache.clone();
+                    let icon_cache2 = icon_cache.clone();
+                    let _ = cx.update(|app_cx| {
+                        open_picker(
+                            &reloaded_config,
+                            &current2,
+                            &tracker2,
+                            window_counts2,
+                            window_cache2,
+                            preview_cache2,
+                            icon_cache2,
+                            false,
+                            app_filter.as_ref(),
+                            app_cx,
+                        );
+                    });
+                }
+                Some(daemon::Command::Activate(window_id)) => {
+                    // An id that no longer exists is simply not activated.
+                    let restore = crate::config::load_alt_tab_config().minimized_activate;
+                    let outcome = cx
+                        .background_executor()
+                        .spawn(async move { platform::activate_window(window_id, restore) })
+                        .await;
+                    match outcome {
+                        platform::Activation::Activated => {}
+                        platform::Activation::Gone => eprintln!("[alt-tab] --activate: no window {}", window_id),
+                        platform::Activation::Refused => {
+                            eprintln!("[alt-tab] --activate: switching to {} was refused", window_id)
+                        }
+                    }
+                }
+                Some(daemon::Command::SwitchRecentOnMonitor) => {
+                    // Fresh bounds, not the prewarm cache: windows may have
+                    // moved since it was filled.
+                    let known = cx
+                        .background_executor()
+                        .spawn(async move { platform::get_open_windows(false, false) })
+                        .await;
+                    let Ok(target) = cx.update(|app_cx| {
+                        recent_on_active_monitor(&known, &tracker_clone, app_cx)
+                    }) else {
+                        continue;
+                    };
+                    let Some(window_id) = target else {
+                        continue;
+                    };
+                    let outcome = cx
+                        .background_executor()
+                        .spawn(async move {
+                            platform::activate_window(window_id, MinimizedActivate::Restore)
+                        })
+                        .await;
+                    if outcome == platform::Activation::Activated {
+                        crate::focus_history::note_frontmost(window_id);
+                    }
+                }
+                Some(daemon::Command::SetProfile(name)) => {
+                    // Applies from the next open and prewarm pass, which reload the config.
+                    crate::config::set_active_profile(name);
+                }
+                Some(daemon::Command::Dump(kind, path)) => {
+                    let known = window_cache.lock().map(|w| w.clone()).unwrap_or_default();
+                    let json = match kind {
+                        daemon::DumpKind::Mru => mru_json(&known),
+                        daemon::DumpKind::Windows => windows_json(&known),
+                    };
+                    write_dump(&path, &json);
+                }
                 Some(daemon::Command::Kill) | None => {
                     #[cfg(debug_assertions)]
                     eprintln!("[alt-tab/daemon] shutting down");
@@ -175,3 +359,129 @@ pub(crate) fn run_app(
         .detach();
     });
 }
+
+/// Write a `--mru` / `--list` reply into the file the CLI reserved for it.
+fn write_dump(path: &std::path::Path, json: &str) {
+    if let Err(e) = crate::dump::write(path, json) {
+        eprintln!("[alt-tab] refusing dump to {}: {}", path.display(), e);
+    }
+}
+
+/// Window list for `--list` as of the last prewarm refresh, in z-order.
+fn windows_json(known: &[WindowInfo]) -> String {
+    let entries: Vec<serde_json::Value> = known
+        .iter()
+        .map(|w| {
+            serde_json::json!({
+                "id": w.id,
+                "app": w.app_name,
+                "title": w.title,
+                "is_minimized": w.is_minimized,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Focus-recency list for `--mru`, most recent first. App and title come from
+/// the last refresh and are null for windows that have since closed.
+fn mru_json(known: &[WindowInfo]) -> String {
+    let entries: Vec<serde_json::Value> = crate::focus_history::entries()
+        .into_iter()
+        .map(|(id, stamp, at)| {
+            let window = known.iter().find(|w| w.id == id);
+            let focused_at_ms = at
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            serde_json::json!({
+                "id": id,
+                "app": window.map(|w| w.app_name.as_str()),
+                "title": window.map(|w| w.title.as_str()),
+                "stamp": stamp,
+                "focused_at_ms": focused_at_ms,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Poll the switch modifier until it is released or `window` elapses.
+/// True if it was released in time (a tap).
+async fn released_within(cx: &AsyncApp, modifier: Modifier, window: Duration) -> bool {
+    let start = std::time::Instant::now();
+    while start.elapsed() < window {
+        if !platform::is_modifier_held(modifier) {
+            return true;
+        }
+        cx.background_executor()
+            .timer(Duration::from_millis(TAP_POLL_INTERVAL_MS))
+            .await;
+    }
+    !platform::is_modifier_held(modifier)
+}
+
+/// Discard queued Show commands, returning the first other command, if any.
+fn drain_shows(rx: &Mutex<mpsc::Receiver<daemon::Command>>) -> Option<daemon::Command> {
+    let rx = rx.lock().ok()?;
+    loop {
+        match rx.try_recv() {
+            Ok(daemon::Command::Show | daemon::Command::ShowReverse) => continue,
+            Ok(other) => return Some(other),
+            Err(_) => return None,
+        }
+    }
+}
+
+/// `--switch-recent-on-monitor`: the most recently focused visible window on
+/// the tracker's active monitor, passing over the frontmost window.
+fn recent_on_active_monitor(
+    known: &[WindowInfo],
+    tracker: &MonitorTracker,
+    cx: &App,
+) -> Option<u32> {
+    let as_tuple = |b: Bounds<Pixels>| {
+        (
+            b.origin.x.to_f64() as f32,
+            b.origin.y.to_f64() as f32,
+            b.size.width.to_f64() as f32,
+            b.size.height.to_f64() as f32,
+        )
+    };
+    let monitors: Vec<(f32, f32, f32, f32)> =
+        cx.displays().iter().map(|d| as_tuple(d.bounds())).collect();
+    let (active, _) = tracker.snapshot()?;
+    let (ax, ay, _, _) = as_tuple(active.bounds());
+    let monitor = monitors
+        .iter()
+        .position(|&(x, y, _, _)| (x - ax).abs() < 1.0 && (y - ay).abs() < 1.0)?;
+    let windows: Vec<(u32, (f32, f32, f32, f32))> = known
+        .iter()
+        .filter(|w| !w.is_minimized)
+        .map(|w| (w.id, (w.x, w.y, w.width, w.height)))
+        .collect();
+    let mru: Vec<u32> = crate::focus_history::entries()
+        .into_iter()
+        .skip(1)
+        .map(|(id, _, _)| id)
+        .collect();
+    most_recent_on_monitor(&mru, &windows, &monitors, monitor)
+}
+
+/// Tap action: raise the window behind the frontmost one, never showing the grid.
+/// With `skip_same_app`, the frontmost app's other windows are passed over.
+fn switch_to_previous(include_desktop: bool, skip_same_app: bool) {
+    let windows = platform::get_on_screen_windows(include_desktop);
+    let mut visible = windows.iter().filter(|w| !w.is_minimized);
+    let Some(front) = visible.next() else {
+        return;
+    };
+    let Some(previous) = visible.find(|w| !skip_same_app || w.app_name != front.app_name) else {
+        return;
+    };
+    #[cfg(debug_assertions)]
+    eprintln!("[alt-tab/tap] tap — switching to {} without the grid", previous.id);
+    if platform::activate_window(previous.id, MinimizedActivate::Restore) == platform::Activation::Activated {
+        crate::focus_history::note_frontmost(previous.id);
+    }
+}
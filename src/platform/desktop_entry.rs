@@ -0,0 +1,132 @@
+//! Launch commands from installed `.desktop` files, matched to a window by
+//! its WM_CLASS. Only compiled on Linux.
+
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+
+/// The `Exec` line (field codes removed) of the desktop entry for `wm_class`:
+/// one whose `StartupWMClass` matches, else one whose file name does.
+pub(super) fn exec_for_class(wm_class: &str) -> Option<String> {
+    let mut by_name = None;
+    for dir in application_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                continue;
+            }
+            let Ok(contents) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Some(parsed) = parse(&contents) else {
+                continue;
+            };
+            if parsed
+                .startup_wm_class
+                .is_some_and(|c| c.eq_ignore_ascii_case(wm_class))
+            {
+                return Some(strip_field_codes(parsed.exec));
+            }
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            let last = stem.rsplit('.').next().unwrap_or(stem);
+            if by_name.is_none() && last.eq_ignore_ascii_case(wm_class) {
+                by_name = Some(strip_field_codes(parsed.exec));
+            }
+        }
+    }
+    by_name
+}
+
+/// `$XDG_DATA_HOME/applications` then each `$XDG_DATA_DIRS` entry, in priority order.
+fn application_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    home.into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .map(|d| d.join("applications"))
+        .collect()
+}
+
+struct Entry<'a> {
+    exec: &'a str,
+    startup_wm_class: Option<&'a str>,
+}
+
+/// Keys of the `[Desktop Entry]` group; None for hidden entries or ones without `Exec`.
+fn parse(contents: &str) -> Option<Entry<'_>> {
+    let mut in_main = false;
+    let mut exec = None;
+    let mut startup_wm_class = None;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_main = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Exec" => exec = Some(value.trim()),
+            "StartupWMClass" => startup_wm_class = Some(value.trim()),
+            "NoDisplay" | "Hidden" if value.trim() == "true" => return None,
+            _ => {}
+        }
+    }
+    Some(Entry { exec: exec.filter(|e| !e.is_empty())?, startup_wm_class })
+}
+
+/// Drop `%f`, `%U` and friends (no files are passed); `%%` is a literal `%`.
+fn strip_field_codes(exec: &str) -> String {
+    let mut out = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        if let Some('%') = chars.next() {
+            out.push('%');
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_main_group_only() {
+        let entry = parse(
+            "[Desktop Entry]\nName=Code\nExec=/usr/bin/code --new-window %F\nStartupWMClass=Code\n\n\
+             [Desktop Action new-empty-window]\nExec=/usr/bin/code --other\n",
+        )
+        .unwrap();
+        assert_eq!(entry.exec, "/usr/bin/code --new-window %F");
+        assert_eq!(entry.startup_wm_class, Some("Code"));
+    }
+
+    #[test]
+    fn hidden_or_execless_entries_are_skipped() {
+        assert!(parse("[Desktop Entry]\nExec=foo\nNoDisplay=true\n").is_none());
+        assert!(parse("[Desktop Entry]\nName=Foo\n").is_none());
+    }
+
+    #[test]
+    fn field_codes_are_removed() {
+        assert_eq!(strip_field_codes("firefox %u"), "firefox");
+        assert_eq!(strip_field_codes("app --name=%c %F --x"), "app --name= --x");
+        assert_eq!(strip_field_codes("printf 100%%"), "printf 100%");
+    }
+}
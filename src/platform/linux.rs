@@ -1,8 +1,12 @@
 use super::RgbaImage;
+use crate::config::{MinimizedActivate, Modifier, PreviewQuality};
 use super::WindowInfo;
 use x11rb::connection::Connection;
 use x11rb::protocol::xproto::ConnectionExt as _;
 use x11rb::protocol::xproto::*;
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Clone, Copy)]
 struct ChannelOrder {
@@ -29,7 +33,7 @@ pub fn dismiss_picker(window: &mut gpui::Window) {
     window.minimize_window();
 }
 
-pub fn is_modifier_held() -> bool {
+pub fn is_modifier_held(modifier: Modifier) -> bool {
     let Ok((conn, _)) = x11rb::connect(None) else {
         return false;
     };
@@ -39,9 +43,22 @@ pub fn is_modifier_held() -> bool {
     let Ok(keymap) = reply.reply() else {
         return false;
     };
-    let alt_l_held = keymap.keys[64 / 8] & (1 << (64 % 8)) != 0;
-    let alt_r_held = keymap.keys[108 / 8] & (1 << (108 % 8)) != 0;
-    alt_l_held || alt_r_held
+    // Left and right keycodes on a standard evdev layout.
+    let (left, right): (usize, usize) = match modifier {
+        Modifier::Alt => (64, 108),
+        Modifier::Control => (37, 105),
+        Modifier::Command => (133, 134),
+        Modifier::Shift => (50, 62),
+    };
+    let held = |code: usize| keymap.keys[code / 8] & (1 << (code % 8)) != 0;
+    held(left) || held(right)
+}
+
+pub fn cursor_position() -> Option<(f32, f32)> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots[screen_num].root;
+    let reply = conn.query_pointer(root).ok()?.reply().ok()?;
+    Some((reply.root_x as f32, reply.root_y as f32))
 }
 
 pub fn is_shift_held() -> bool {
@@ -60,12 +77,105 @@ pub fn is_shift_held() -> bool {
     shift_l || shift_r
 }
 
-pub fn activate_window(window_id: u32) {
-    std::process::Command::new("xdotool")
+/// Whether an X server (including Xwayland) answers. Cached: it doesn't change
+/// for the session, and without one windows come from the Wayland protocol.
+fn x11_reachable() -> bool {
+    static REACHABLE: OnceLock<bool> = OnceLock::new();
+    *REACHABLE.get_or_init(|| x11rb::connect(None).is_ok())
+}
+
+pub fn activate_window(window_id: u32, restore: MinimizedActivate) -> super::Activation {
+    if !x11_reachable() {
+        return super::wayland::activate(window_id);
+    }
+    if restore == MinimizedActivate::RestoreNoAnimation {
+        map_if_hidden(window_id);
+    }
+    // xdotool sends _NET_ACTIVE_WINDOW, which also un-minimizes. A failure is
+    // only Gone when the window itself is; a missing xdotool is not.
+    match std::process::Command::new("xdotool")
         .arg("windowactivate")
         .arg(window_id.to_string())
         .status()
-        .ok();
+    {
+        Ok(status) if status.success() => super::Activation::Activated,
+        Ok(_) if !window_exists(window_id) => super::Activation::Gone,
+        _ => super::Activation::Refused,
+    }
+}
+
+/// ICCCM iconic-to-normal transition: map a minimized window ourselves, so the
+/// WM handles a plain map request instead of running its deiconify animation.
+/// `_NET_ACTIVE_WINDOW` afterwards still raises and focuses it.
+fn map_if_hidden(window_id: u32) {
+    let Ok((conn, _)) = x11rb::connect(None) else {
+        return;
+    };
+    let intern = |name: &[u8]| {
+        conn.intern_atom(false, name)
+            .ok()
+            .and_then(|c| c.reply().ok())
+            .map(|r| r.atom)
+    };
+    let (Some(state_atom), Some(hidden_atom)) = (intern(b"_NET_WM_STATE"), intern(b"_NET_WM_STATE_HIDDEN"))
+    else {
+        return;
+    };
+    let hidden = conn
+        .get_property(false, window_id, state_atom, AtomEnum::ATOM, 0, 64)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .and_then(|r| r.value32().map(|mut atoms| atoms.any(|a| a == hidden_atom)))
+        .unwrap_or(false);
+    if hidden {
+        let _ = conn.map_window(window_id);
+        let _ = conn.flush();
+    }
+}
+
+/// Whether the X server still knows `window_id`; errs on the side of true.
+fn window_exists(window_id: u32) -> bool {
+    let Ok((conn, _)) = x11rb::connect(None) else {
+        return true;
+    };
+    match conn.get_window_attributes(window_id) {
+        Ok(cookie) => cookie.reply().is_ok(),
+        Err(_) => true,
+    }
+}
+
+pub fn move_window_to_monitor(window_id: u32, monitor: (f32, f32, f32, f32)) -> bool {
+    let Ok((conn, screen_num)) = x11rb::connect(None) else {
+        return false;
+    };
+    let root = conn.setup().roots[screen_num].root;
+    let Some(geometry) = conn.get_geometry(window_id).ok().and_then(|c| c.reply().ok()) else {
+        return false;
+    };
+    let Some(origin) = conn
+        .translate_coordinates(window_id, root, 0, 0)
+        .ok()
+        .and_then(|c| c.reply().ok())
+    else {
+        return false;
+    };
+    let window = (
+        origin.dst_x as f32,
+        origin.dst_y as f32,
+        geometry.width as f32,
+        geometry.height as f32,
+    );
+    let Some((x, y)) = super::position_on_monitor(window, monitor) else {
+        return false;
+    };
+    std::process::Command::new("xdotool")
+        .arg("windowmove")
+        .arg(window_id.to_string())
+        .arg(x.to_string())
+        .arg(y.to_string())
+        .status()
+        .ok()
+        .is_some_and(|s| s.success())
 }
 
 pub fn move_app_window(title: &str, x: i32, y: i32) -> bool {
@@ -81,11 +191,12 @@ pub fn move_app_window(title: &str, x: i32, y: i32) -> bool {
         .is_some_and(|s| s.success())
 }
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
+pub fn get_open_windows(_include_other_spaces: bool, include_desktop: bool) -> Vec<WindowInfo> {
     let mut windows = Vec::new();
 
     let Ok((conn, screen_num)) = x11rb::connect(None) else {
-        return windows;
+        // Pure Wayland: titles and app ids from the compositor, no previews.
+        return super::wayland::toplevels().unwrap_or(windows);
     };
 
     let root = conn.setup().roots[screen_num].root;
@@ -98,6 +209,7 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
         "UTF8_STRING",
         "_NET_WM_WINDOW_TYPE",
         "_NET_WM_WINDOW_TYPE_NORMAL",
+        "_NET_WM_WINDOW_TYPE_DESKTOP",
         "_NET_WM_STATE",
         "_NET_WM_STATE_HIDDEN",
         "WM_CLASS",
@@ -161,9 +273,14 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
         .get("_NET_WM_WINDOW_TYPE_NORMAL")
         .copied()
         .unwrap_or(0);
+    let desktop_atom = atom_map
+        .get("_NET_WM_WINDOW_TYPE_DESKTOP")
+        .copied()
+        .unwrap_or(0);
 
     // Filter IDs by type
     let mut filtered_ids = Vec::new();
+    let mut desktop_ids = std::collections::HashSet::new();
     for (i, cookie) in type_cookies.into_iter().enumerate() {
         let mut is_normal = true;
         if let Some(tp) = cookie.and_then(|c| c.reply().ok()) {
@@ -176,6 +293,11 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
                         found_normal = true;
                         break;
                     }
+                    if include_desktop && desktop_atom != 0 && t == desktop_atom {
+                        desktop_ids.insert(ids[i]);
+                        found_normal = true;
+                        break;
+                    }
                 }
                 if has_any_type && !found_normal {
                     is_normal = false;
@@ -236,6 +358,16 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
         }
     }
 
+    // Pipelined geometry: size from the window, origin translated to the root
+    let mut geometry_cookies: Vec<_> = filtered_ids
+        .iter()
+        .map(|&id| conn.get_geometry(id).ok())
+        .collect();
+    let mut origin_cookies: Vec<_> = filtered_ids
+        .iter()
+        .map(|&id| conn.translate_coordinates(id, root, 0, 0).ok())
+        .collect();
+
     for (i, &id) in filtered_ids.iter().enumerate().rev() {
         let mut title = String::new();
 
@@ -251,19 +383,11 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
             }
         }
 
-        let mut app_name = String::new();
-        if let Some(reply) = wm_class_cookies[i].take().and_then(|c| c.reply().ok()) {
-            let parts: Vec<&str> = std::str::from_utf8(&reply.value)
-                .unwrap_or("")
-                .split('\0')
-                .filter(|s| !s.is_empty())
-                .collect();
-            if parts.len() >= 2 {
-                app_name = parts[1].to_string();
-            } else if !parts.is_empty() {
-                app_name = parts[0].to_string();
-            }
-        }
+        let mut app_name = wm_class_cookies[i]
+            .take()
+            .and_then(|c| c.reply().ok())
+            .map(|reply| app_name_from_wm_class(&reply.value))
+            .unwrap_or_default();
 
         let icon = icon_cookies[i]
             .take()
@@ -280,21 +404,37 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
             })
             .unwrap_or(false);
 
+        // Zero bounds when the server cannot answer; monitor filters skip those.
+        let geometry = geometry_cookies[i].take().and_then(|c| c.reply().ok());
+        let origin = origin_cookies[i].take().and_then(|c| c.reply().ok());
+        let (x, y, width, height) = match (geometry, origin) {
+            (Some(g), Some(o)) => (o.dst_x as f32, o.dst_y as f32, g.width as f32, g.height as f32),
+            _ => (0.0, 0.0, 0.0, 0.0),
+        };
+
+        let is_desktop = desktop_ids.contains(&id) || title == "Desktop";
+        if is_desktop && !include_desktop {
+            continue;
+        }
+        if is_desktop {
+            // Desktop windows often have no title and a file-manager WM_CLASS.
+            title = "Desktop".to_string();
+            app_name = "Desktop".to_string();
+        }
         if !title.is_empty() {
-            if title == "Desktop" {
-                continue;
-            }
             windows.push(WindowInfo {
                 id,
                 title,
                 app_name,
                 preview_path: None,
                 icon,
-                x: 0.0,
-                y: 0.0,
-                width: 0.0,
-                height: 0.0,
+                x,
+                y,
+                width,
+                height,
                 is_minimized,
+                is_off_space: false,
+                has_unsaved_changes: false,
             });
         }
     }
@@ -305,14 +445,71 @@ pub fn get_open_windows() -> Vec<WindowInfo> {
     windows
 }
 
-pub fn get_on_screen_windows() -> Vec<WindowInfo> {
-    get_open_windows()
+/// Root-window property watcher on its own long-lived connection. The root's
+/// client list, stacking order and active window change whenever a window opens,
+/// closes, is raised or minimized, so those three properties cover the list.
+struct ClientListWatcher {
+    conn: RustConnection,
+    atoms: [Atom; 3],
+}
+
+static CLIENT_LIST_WATCHER: OnceLock<Option<Mutex<ClientListWatcher>>> = OnceLock::new();
+
+impl ClientListWatcher {
+    fn start() -> Option<Self> {
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots[screen_num].root;
+        let intern = |name: &[u8]| -> Option<Atom> {
+            Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+        };
+        let atoms = [
+            intern(b"_NET_CLIENT_LIST")?,
+            intern(b"_NET_CLIENT_LIST_STACKING")?,
+            intern(b"_NET_ACTIVE_WINDOW")?,
+        ];
+        conn.change_window_attributes(
+            root,
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .ok()?
+        .check()
+        .ok()?;
+        conn.flush().ok()?;
+        Some(Self { conn, atoms })
+    }
+
+    /// Drain queued events; `None` if the connection broke.
+    fn drain(&self) -> Option<bool> {
+        let mut changed = false;
+        loop {
+            match self.conn.poll_for_event() {
+                Ok(Some(Event::PropertyNotify(e))) if self.atoms.contains(&e.atom) => changed = true,
+                Ok(Some(_)) => {}
+                Ok(None) => return Some(changed),
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+pub fn window_list_changed() -> Option<bool> {
+    let watcher = CLIENT_LIST_WATCHER
+        .get_or_init(|| ClientListWatcher::start().map(Mutex::new))
+        .as_ref()?;
+    watcher.lock().ok()?.drain()
+}
+
+pub fn get_on_screen_windows(include_desktop: bool) -> Vec<WindowInfo> {
+    get_open_windows(false, include_desktop)
 }
 
 pub fn capture_previews_cg(
     targets: &[(usize, u32)],
     _max_w: usize,
     _max_h: usize,
+    _quality: PreviewQuality,
+    _capture_cursor: bool,
+    _screen_capture_kit: bool,
 ) -> Vec<(usize, Option<RgbaImage>)> {
     targets.iter().map(|&(idx, _)| (idx, None)).collect()
 }
@@ -432,6 +629,173 @@ fn x11_data_to_rgba(
     Some(rgba)
 }
 
+pub fn environment_checks() -> Vec<(&'static str, bool)> {
+    let x11 = x11rb::connect(None).is_ok();
+    let wayland_only = std::env::var_os("WAYLAND_DISPLAY").is_some() && !x11;
+    vec![
+        ("X11 display reachable (Xwayland counts)", x11),
+        (
+            "window list available (X11, or Wayland foreign-toplevel protocol)",
+            !wayland_only || super::wayland::available(),
+        ),
+        ("xdotool available for activation", xdotool_available()),
+    ]
+}
+
+fn xdotool_available() -> bool {
+    std::process::Command::new("xdotool")
+        .arg("version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+pub fn frontmost_has_modal() -> bool {
+    let Ok((conn, screen_num)) = x11rb::connect(None) else {
+        return false;
+    };
+    let root = conn.setup().roots[screen_num].root;
+    let intern = |name: &[u8]| {
+        conn.intern_atom(false, name)
+            .ok()
+            .and_then(|c| c.reply().ok())
+            .map(|r| r.atom)
+    };
+    let (Some(active_atom), Some(state_atom), Some(modal_atom)) = (
+        intern(b"_NET_ACTIVE_WINDOW"),
+        intern(b"_NET_WM_STATE"),
+        intern(b"_NET_WM_STATE_MODAL"),
+    ) else {
+        return false;
+    };
+    let Some(active) = conn
+        .get_property(false, root, active_atom, AtomEnum::WINDOW, 0, 1)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .and_then(|r| r.value32().and_then(|mut v| v.next()))
+        .filter(|id| *id != 0)
+    else {
+        return false;
+    };
+    conn.get_property(false, active, state_atom, AtomEnum::ATOM, 0, 64)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .and_then(|r| r.value32().map(|mut atoms| atoms.any(|a| a == modal_atom)))
+        .unwrap_or(false)
+}
+
+/// WM_CLASS is "instance\0class\0"; the class names the app, the instance is
+/// the fallback when it is missing.
+fn app_name_from_wm_class(value: &[u8]) -> String {
+    let parts: Vec<&str> = std::str::from_utf8(value)
+        .unwrap_or("")
+        .split('\0')
+        .filter(|s| !s.is_empty())
+        .collect();
+    parts.get(1).or(parts.first()).map(|s| s.to_string()).unwrap_or_default()
+}
+
+/// App of `_NET_ACTIVE_WINDOW`, named from its WM_CLASS like the window list.
+pub fn frontmost_app_name() -> Option<String> {
+    if !x11_reachable() {
+        return super::wayland::activated_app_id();
+    }
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots[screen_num].root;
+    let active_atom = conn
+        .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    let active = conn
+        .get_property(false, root, active_atom, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .next()
+        .filter(|id| *id != 0)?;
+    let class = conn
+        .get_property(false, active, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 1024)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(app_name_from_wm_class(&class.value)).filter(|name| !name.is_empty())
+}
+
+fn find_window_by_name(title: &str) -> Option<u32> {
+    let output = std::process::Command::new("xdotool")
+        .arg("search")
+        .arg("--name")
+        .arg(title)
+        .output()
+        .ok()?;
+    std::str::from_utf8(&output.stdout)
+        .ok()?
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
+
+pub fn set_window_always_on_top(title: &str, on_top: bool) {
+    let Some(window_id) = find_window_by_name(title) else {
+        return;
+    };
+    let Ok((conn, screen_num)) = x11rb::connect(None) else {
+        return;
+    };
+    let root = conn.setup().roots[screen_num].root;
+    let intern = |name: &[u8]| {
+        conn.intern_atom(false, name)
+            .ok()
+            .and_then(|c| c.reply().ok())
+            .map(|r| r.atom)
+    };
+    let (Some(state_atom), Some(above_atom)) = (intern(b"_NET_WM_STATE"), intern(b"_NET_WM_STATE_ABOVE"))
+    else {
+        return;
+    };
+    // EWMH _NET_WM_STATE request: action 1 = add, 0 = remove; source 1 = application.
+    let action = if on_top { 1 } else { 0 };
+    let event = ClientMessageEvent::new(32, window_id, state_atom, [action, above_atom, 0, 1, 0]);
+    let _ = conn.send_event(
+        false,
+        root,
+        EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+        event,
+    );
+    let _ = conn.flush();
+}
+
+/// GNOME 47+ `accent-color`, mapped to the libadwaita palette.
+pub fn system_accent_color() -> Option<u32> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "accent-color"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    match String::from_utf8_lossy(&output.stdout).trim().trim_matches('\'') {
+        "blue" => Some(0x3584e4),
+        "teal" => Some(0x2190a4),
+        "green" => Some(0x3a944a),
+        "yellow" => Some(0xc88800),
+        "orange" => Some(0xed5b00),
+        "red" => Some(0xe62d42),
+        "pink" => Some(0xd56199),
+        "purple" => Some(0x9141ac),
+        "slate" => Some(0x6f8396),
+        _ => None,
+    }
+}
+
+/// GNOME-style `enable-animations`; desktops without gsettings count as no preference.
+pub fn prefers_reduced_motion() -> bool {
+    std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "enable-animations"])
+        .output()
+        .map(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).trim() == "false")
+        .unwrap_or(false)
+}
+
 pub fn disable_window_shadow() {}
 
 pub fn close_window(window_id: u32) {
@@ -442,6 +806,11 @@ pub fn close_window(window_id: u32) {
         .ok();
 }
 
+/// X11 has no standard "document edited" hint.
+pub fn window_has_unsaved_changes(_window_id: u32) -> bool {
+    false
+}
+
 pub fn quit_app(window_id: u32) {
     // Get the PID of the window's owning process and send SIGTERM for a clean quit.
     let Ok(output) = std::process::Command::new("xdotool")
@@ -459,6 +828,36 @@ pub fn quit_app(window_id: u32) {
     }
 }
 
+/// Run the desktop entry matching the window's WM_CLASS, or else re-run the
+/// owning process's executable. Single-instance apps usually respond with a
+/// new window; others may start a second instance.
+pub fn open_new_window(window_id: u32, app_name: &str) -> bool {
+    let command = super::desktop_entry::exec_for_class(app_name).or_else(|| {
+        let output = std::process::Command::new("xdotool")
+            .arg("getwindowpid")
+            .arg(window_id.to_string())
+            .output()
+            .ok()?;
+        let pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let exe = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+        Some(format!("'{}'", exe.to_string_lossy().replace('\'', "'\\''")))
+    });
+    let Some(command) = command else {
+        return false;
+    };
+    match std::process::Command::new("sh").arg("-c").arg(&command).spawn() {
+        // Reap the child so it doesn't linger as a zombie under the daemon.
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+            true
+        }
+        Err(e) => {
+            eprintln!("[alt-tab] failed to launch '{}': {}", command, e);
+            false
+        }
+    }
+}
+
 pub fn minimize_window_by_id(window_id: u32) {
     std::process::Command::new("xdotool")
         .arg("windowminimize")
@@ -467,6 +866,11 @@ pub fn minimize_window_by_id(window_id: u32) {
         .ok();
 }
 
+/// X11 has no badge concept.
+pub fn get_app_badges() -> std::collections::HashMap<String, String> {
+    std::collections::HashMap::new()
+}
+
 pub fn get_app_icons(windows: &[WindowInfo]) -> std::collections::HashMap<String, RgbaImage> {
     let mut icons = std::collections::HashMap::new();
     for win in windows {
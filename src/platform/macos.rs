@@ -1,4 +1,6 @@
 use super::cg_helpers;
+use crate::config::{Modifier, PreviewQuality};
+use crate::preview::{downscale_4bpp, draw_cursor_marker};
 use super::RgbaImage;
 use super::WindowInfo;
 use std::collections::{HashMap, HashSet};
@@ -193,6 +195,8 @@ struct WindowEnumeration {
     on_screen_ids: HashSet<u32>,
     on_screen_pids: HashSet<i32>,
     on_screen_count_by_pid: HashMap<i32, usize>,
+    /// Off-screen windows already listed (minimized or off-space).
+    listed_ids: HashSet<u32>,
 }
 
 impl WindowEnumeration {
@@ -218,10 +222,21 @@ impl WindowEnumeration {
             width: window.w,
             height: window.h,
             is_minimized: false,
+            is_off_space: false,
+            has_unsaved_changes: false,
         });
     }
 
     fn push_minimized(&mut self, window: &CgWindow, title: String) {
+        self.push_off_screen(window, title, true);
+    }
+
+    fn push_off_space(&mut self, window: &CgWindow, title: String) {
+        self.push_off_screen(window, title, false);
+    }
+
+    fn push_off_screen(&mut self, window: &CgWindow, title: String, is_minimized: bool) {
+        self.listed_ids.insert(window.id);
         self.windows.push(WindowInfo {
             id: window.id,
             title,
@@ -232,7 +247,9 @@ impl WindowEnumeration {
             y: window.y,
             width: window.w,
             height: window.h,
-            is_minimized: true,
+            is_minimized,
+            is_off_space: !is_minimized,
+            has_unsaved_changes: false,
         });
     }
 }
@@ -429,6 +446,67 @@ fn collect_minimized_windows(
     unsafe { CFRelease(list as *const c_void) };
 }
 
+/// Windows that are neither on screen nor minimized, which on macOS means they
+/// live on another Space. AX usually only reports current-Space windows, so a
+/// window is accepted when AX knows its `_AXWindowID` as non-minimized, or when
+/// AX doesn't know it but it was seen on screen earlier for the same process.
+/// Not remembered in the tracker so minimized-window budgets stay unaffected.
+fn collect_off_space_windows(
+    own_pid: i32,
+    state: &mut WindowEnumeration,
+    tracker: &mut KnownWindowTracker,
+) {
+    let options = K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS;
+    let list = unsafe { CGWindowListCopyWindowInfo(options, K_CG_NULL_WINDOW_ID) };
+    if list.is_null() {
+        return;
+    }
+
+    let mut regular_app_cache: HashMap<i32, bool> = HashMap::new();
+    let mut ax_windows_cache: HashMap<i32, Option<HashMap<u32, AxWindowMeta>>> = HashMap::new();
+
+    for window in parse_cg_window_list(list, own_pid) {
+        if state.on_screen_ids.contains(&window.id) || state.listed_ids.contains(&window.id) {
+            continue;
+        }
+        if !window.has_title || window.w < 1.0 || window.h < 1.0 {
+            continue;
+        }
+        let is_regular = *regular_app_cache
+            .entry(window.pid)
+            .or_insert_with(|| is_regular_app(window.pid));
+        if !is_regular {
+            continue;
+        }
+
+        let ax_windows = ax_windows_cache
+            .entry(window.pid)
+            .or_insert_with(|| ax_windows(window.pid));
+        let mut title = window.title.clone();
+        match ax_windows.as_ref().and_then(|m| m.get(&window.id)) {
+            Some(meta) if meta.is_minimized => continue,
+            Some(meta) => {
+                if !meta.title.is_empty() {
+                    title = meta.title.clone();
+                }
+            }
+            None => {
+                let seen_before = tracker
+                    .identity_for_pid(window.pid)
+                    .and_then(|id| tracker.snapshot.get(&id))
+                    .is_some_and(|ids| ids.contains(&window.id));
+                if !seen_before {
+                    continue;
+                }
+            }
+        }
+
+        state.push_off_space(&window, title);
+    }
+
+    unsafe { CFRelease(list as *const c_void) };
+}
+
 /// Shared helper: parse normal-layer windows from a CG window list.
 fn parse_cg_window_list(list: *const c_void, own_pid: i32) -> Vec<CgWindow> {
     let key_layer = cg_helpers::cfstr(b"kCGWindowLayer");
@@ -498,13 +576,18 @@ fn parse_cg_window_list(list: *const c_void, own_pid: i32) -> Vec<CgWindow> {
     result
 }
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
-    get_open_windows_impl(true)
+/// `include_desktop` is Linux-only; CG enumeration always excludes desktop elements.
+pub fn get_open_windows(include_other_spaces: bool, _include_desktop: bool) -> Vec<WindowInfo> {
+    get_open_windows_impl(true, include_other_spaces)
+}
+
+pub fn window_list_changed() -> Option<bool> {
+    None
 }
 
 /// Fast path: CG window list + AX dedup only. Skips KnownWindowTracker
 /// (which calls proc_pidinfo per PID) and minimized window collection.
-pub fn get_on_screen_windows() -> Vec<WindowInfo> {
+pub fn get_on_screen_windows(_include_desktop: bool) -> Vec<WindowInfo> {
     let own_pid = std::process::id() as i32;
     let options =
         K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS;
@@ -528,12 +611,14 @@ pub fn get_on_screen_windows() -> Vec<WindowInfo> {
             width: window.w,
             height: window.h,
             is_minimized: false,
+            is_off_space: false,
+            has_unsaved_changes: false,
         });
     }
     windows
 }
 
-fn get_open_windows_impl(include_minimized: bool) -> Vec<WindowInfo> {
+fn get_open_windows_impl(include_minimized: bool, include_other_spaces: bool) -> Vec<WindowInfo> {
     let own_pid = std::process::id() as i32;
     let mut state = WindowEnumeration::default();
     let mut tracker = KnownWindowTracker::new();
@@ -542,6 +627,9 @@ fn get_open_windows_impl(include_minimized: bool) -> Vec<WindowInfo> {
     if include_minimized {
         collect_minimized_windows(own_pid, &mut state, &mut tracker);
     }
+    if include_other_spaces {
+        collect_off_space_windows(own_pid, &mut state, &mut tracker);
+    }
     tracker.persist();
 
     state.windows
@@ -823,17 +911,126 @@ pub fn get_app_icons(windows: &[WindowInfo]) -> HashMap<String, RgbaImage> {
     icons
 }
 
+/// Badge labels from the Dock's AX tree: each `AXDockItem` carries the app name
+/// in `AXTitle` and the badge text in `AXStatusLabel`.
+pub fn get_app_badges() -> HashMap<String, String> {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateApplication(pid: i32) -> *const c_void;
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *const c_void,
+        ) -> i32;
+    }
+
+    let dock_pid = objc2::rc::autoreleasepool(|_pool| {
+        use objc2_app_kit::NSWorkspace;
+
+        NSWorkspace::sharedWorkspace()
+            .runningApplications()
+            .iter()
+            .find(|app| {
+                app.bundleIdentifier()
+                    .is_some_and(|id| id.to_string() == "com.apple.dock")
+            })
+            .map(|app| app.processIdentifier())
+    });
+    let Some(dock_pid) = dock_pid else {
+        return HashMap::new();
+    };
+
+    let copy_attr = |element: *const c_void, name: &[u8]| -> *const c_void {
+        let attr = cg_helpers::cfstr(name);
+        let mut value: *const c_void = std::ptr::null();
+        let err = unsafe { AXUIElementCopyAttributeValue(element, attr, &mut value) };
+        unsafe { CFRelease(attr as *const c_void) };
+        if err != 0 {
+            return std::ptr::null();
+        }
+        value
+    };
+    let copy_string = |element: *const c_void, name: &[u8]| -> Option<String> {
+        let value = copy_attr(element, name);
+        if value.is_null() {
+            return None;
+        }
+        let s = cg_helpers::cfstring_to_string(value);
+        unsafe { CFRelease(value) };
+        s
+    };
+
+    let mut badges = HashMap::new();
+    unsafe {
+        let dock = AXUIElementCreateApplication(dock_pid);
+        if dock.is_null() {
+            return badges;
+        }
+        let lists = copy_attr(dock, b"AXChildren");
+        CFRelease(dock);
+        if lists.is_null() {
+            return badges;
+        }
+        for i in 0..CFArrayGetCount(lists as CFArrayRef) {
+            let list = CFArrayGetValueAtIndex(lists as CFArrayRef, i);
+            if list.is_null() || copy_string(list, b"AXRole").as_deref() != Some("AXList") {
+                continue;
+            }
+            let items = copy_attr(list, b"AXChildren");
+            if items.is_null() {
+                continue;
+            }
+            for j in 0..CFArrayGetCount(items as CFArrayRef) {
+                let item = CFArrayGetValueAtIndex(items as CFArrayRef, j);
+                if item.is_null() {
+                    continue;
+                }
+                let Some(badge) = copy_string(item, b"AXStatusLabel").filter(|b| !b.is_empty())
+                else {
+                    continue;
+                };
+                if let Some(title) = copy_string(item, b"AXTitle") {
+                    badges.insert(title, badge);
+                }
+            }
+            CFRelease(items);
+        }
+        CFRelease(lists);
+    }
+    badges
+}
+
 pub fn capture_previews_cg(
     targets: &[(usize, u32)],
     max_w: usize,
     max_h: usize,
+    quality: PreviewQuality,
+    capture_cursor: bool,
+    screen_capture_kit: bool,
 ) -> Vec<(usize, Option<RgbaImage>)> {
+    // Single-window captures never contain the cursor; with `capture_cursor`
+    // it is drawn onto the window directly under it.
+    let cursor = if capture_cursor { cursor_over_window() } else { None };
+    // Opt-in ScreenCaptureKit on macOS 14+; CGWindowListCreateImage otherwise.
+    let sck = screen_capture_kit
+        .then(|| {
+            super::sck::capture_windows(targets, max_w, max_h, move |wid, image| {
+                let cursor_at = cursor.filter(|(id, _)| *id == wid).map(|(_, at)| at);
+                let raw = image as *const _ as CGImageRef;
+                extract_bgra_from_raw_cgimage(raw, max_w, max_h, quality, cursor_at)
+            })
+        })
+        .flatten();
+    if let Some(captured) = sck {
+        return captured;
+    }
     std::thread::scope(|s| {
         let handles: Vec<_> = targets
             .iter()
             .map(|&(idx, wid)| {
+                let cursor_at = cursor.filter(|(id, _)| *id == wid).map(|(_, at)| at);
                 s.spawn(move || {
-                    let result = cg_capture_window(wid, max_w, max_h);
+                    let result = cg_capture_window(wid, max_w, max_h, quality, cursor_at);
                     (idx, result)
                 })
             })
@@ -845,7 +1042,33 @@ pub fn capture_previews_cg(
     })
 }
 
-fn cg_capture_window(wid: u32, max_w: usize, max_h: usize) -> Option<RgbaImage> {
+/// The topmost on-screen window under the cursor and the cursor position as a
+/// fraction of that window's size.
+fn cursor_over_window() -> Option<(u32, (f32, f32))> {
+    let (cx, cy) = cursor_position()?;
+    let options =
+        K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS;
+    let list = unsafe { CGWindowListCopyWindowInfo(options, K_CG_NULL_WINDOW_ID) };
+    if list.is_null() {
+        return None;
+    }
+    let parsed = parse_cg_window_list(list, std::process::id() as i32);
+    unsafe { CFRelease(list as *const c_void) };
+    // CG lists windows front to back.
+    parsed
+        .into_iter()
+        .filter(|w| w.w >= 1.0 && w.h >= 1.0)
+        .find(|w| cx >= w.x && cx < w.x + w.w && cy >= w.y && cy < w.y + w.h)
+        .map(|w| (w.id, ((cx - w.x) / w.w, (cy - w.y) / w.h)))
+}
+
+fn cg_capture_window(
+    wid: u32,
+    max_w: usize,
+    max_h: usize,
+    quality: PreviewQuality,
+    cursor_at: Option<(f32, f32)>,
+) -> Option<RgbaImage> {
     let img = unsafe {
         CGWindowListCreateImage(
             CG_RECT_NULL,
@@ -857,12 +1080,18 @@ fn cg_capture_window(wid: u32, max_w: usize, max_h: usize) -> Option<RgbaImage>
     if img.is_null() {
         return None;
     }
-    let result = extract_bgra_from_raw_cgimage(img, max_w, max_h);
+    let result = extract_bgra_from_raw_cgimage(img, max_w, max_h, quality, cursor_at);
     unsafe { CFRelease(img) };
     result
 }
 
-fn extract_bgra_from_raw_cgimage(img: CGImageRef, max_w: usize, max_h: usize) -> Option<RgbaImage> {
+fn extract_bgra_from_raw_cgimage(
+    img: CGImageRef,
+    max_w: usize,
+    max_h: usize,
+    quality: PreviewQuality,
+    cursor_at: Option<(f32, f32)>,
+) -> Option<RgbaImage> {
     let src_w = unsafe { CGImageGetWidth(img) };
     let src_h = unsafe { CGImageGetHeight(img) };
     if src_w == 0 || src_h == 0 {
@@ -890,29 +1119,36 @@ fn extract_bgra_from_raw_cgimage(img: CGImageRef, max_w: usize, max_h: usize) ->
     let offset_x = (max_w - scaled_w) / 2;
     let offset_y = (max_h - scaled_h) / 2;
 
+    let scaled = downscale_4bpp(raw, src_w, src_h, bytes_per_row, scaled_w, scaled_h, quality);
+    unsafe { CFRelease(cf_data) };
+
     let mut bgra = vec![0u8; max_w * max_h * 4];
+    let row_len = scaled_w * 4;
     for y in 0..scaled_h {
-        let src_y = (y * src_h) / scaled_h;
-        let row_start = src_y * bytes_per_row;
-        for x in 0..scaled_w {
-            let src_x = (x * src_w) / scaled_w;
-            let src_off = row_start + src_x * 4;
-            if src_off + 4 > len {
-                continue;
-            }
-            let dst_off = ((offset_y + y) * max_w + offset_x + x) * 4;
-            bgra[dst_off..dst_off + 4].copy_from_slice(&raw[src_off..src_off + 4]);
-        }
+        let src_off = y * row_len;
+        let dst_off = ((offset_y + y) * max_w + offset_x) * 4;
+        bgra[dst_off..dst_off + row_len].copy_from_slice(&scaled[src_off..src_off + row_len]);
+    }
+    if let Some((fx, fy)) = cursor_at {
+        let x = offset_x as f32 + fx * scaled_w as f32;
+        let y = offset_y as f32 + fy * scaled_h as f32;
+        draw_cursor_marker(&mut bgra, max_w, max_h, x, y);
     }
 
-    unsafe { CFRelease(cf_data) };
     Some(RgbaImage { data: bgra, width: max_w, height: max_h })
 }
 
-pub fn activate_window(window_id: u32) {
+/// The Dock owns the un-minimize animation of other apps' windows and offers no
+/// way to skip it, so `RestoreNoAnimation` restores the same way as `Restore`.
+pub fn activate_window(window_id: u32, _restore: crate::config::MinimizedActivate) -> super::Activation {
     let Some((pid, title)) = cg_window_pid_and_title(window_id) else {
-        return;
+        return super::Activation::Gone;
     };
+    // The app may be mid-termination (e.g. just quit from the picker); AX calls
+    // against a dying PID silently do nothing, so bail out and let the caller move on.
+    if !is_process_running(pid) {
+        return super::Activation::Gone;
+    }
 
     // Raise the specific AX window so the correct window comes to front,
     // not just whichever window macOS picks for the app.
@@ -944,15 +1180,140 @@ pub fn activate_window(window_id: u32) {
         }
     }
 
-    // Activate the app so it comes to the foreground.
-    objc2::rc::autoreleasepool(|_pool| {
+    // Activate the app so it comes to the foreground. This can be refused (e.g. a
+    // system modal is up); the window is still there, so the caller must not
+    // treat it as gone and switch to another one.
+    let activated = objc2::rc::autoreleasepool(|_pool| {
         use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
 
-        if let Some(app) = NSRunningApplication::runningApplicationWithProcessIdentifier(pid) {
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid).is_some_and(|app| {
             #[allow(deprecated)]
-            let _ = app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps);
-        }
+            app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps)
+        })
+    });
+    if activated {
+        super::Activation::Activated
+    } else {
+        super::Activation::Refused
+    }
+}
+
+pub fn environment_checks() -> Vec<(&'static str, bool)> {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+    }
+    unsafe {
+        vec![
+            ("Accessibility permission granted", AXIsProcessTrusted()),
+            ("Screen Recording permission granted", CGPreflightScreenCaptureAccess()),
+        ]
+    }
+}
+
+/// `localizedName` of NSWorkspace's frontmost app; CG reports the same name
+/// as each window's owner, so it matches `WindowInfo::app_name`.
+pub fn frontmost_app_name() -> Option<String> {
+    objc2::rc::autoreleasepool(|_pool| {
+        use objc2_app_kit::NSWorkspace;
+
+        NSWorkspace::sharedWorkspace()
+            .frontmostApplication()
+            .filter(|app| app.processIdentifier() != std::process::id() as i32)
+            .and_then(|app| app.localizedName())
+            .map(|name| name.to_string().trim().to_string())
+    })
+    .filter(|name| !name.is_empty())
+}
+
+pub fn frontmost_has_modal() -> bool {
+    let pid = objc2::rc::autoreleasepool(|_pool| {
+        use objc2_app_kit::NSWorkspace;
+
+        NSWorkspace::sharedWorkspace()
+            .frontmostApplication()
+            .map(|app| app.processIdentifier())
     });
+    match pid {
+        Some(pid) => unsafe { ax_focused_window_is_modal(pid) },
+        None => false,
+    }
+}
+
+/// `AXModal` on the focused window, or an `AXSheet` among its children.
+unsafe fn ax_focused_window_is_modal(pid: i32) -> bool {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateApplication(pid: i32) -> *const c_void;
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *const c_void,
+        ) -> i32;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFBooleanTrue: *const c_void;
+    }
+
+    let copy_attr = |element: *const c_void, name: &[u8]| -> *const c_void {
+        let attr = cg_helpers::cfstr(name);
+        let mut value: *const c_void = std::ptr::null();
+        let err = AXUIElementCopyAttributeValue(element, attr, &mut value);
+        CFRelease(attr as *const c_void);
+        if err != 0 {
+            return std::ptr::null();
+        }
+        value
+    };
+
+    let app = AXUIElementCreateApplication(pid);
+    if app.is_null() {
+        return false;
+    }
+    let focused = copy_attr(app, b"AXFocusedWindow");
+    CFRelease(app);
+    if focused.is_null() {
+        return false;
+    }
+
+    // CFBooleans are singletons, don't release them.
+    let mut is_modal = copy_attr(focused, b"AXModal") == kCFBooleanTrue;
+    if !is_modal {
+        let children = copy_attr(focused, b"AXChildren");
+        if !children.is_null() {
+            for i in 0..CFArrayGetCount(children as CFArrayRef) {
+                let child = CFArrayGetValueAtIndex(children as CFArrayRef, i);
+                if child.is_null() {
+                    continue;
+                }
+                let role = copy_attr(child, b"AXRole");
+                if !role.is_null() {
+                    is_modal = cg_helpers::cfstring_to_string(role).as_deref() == Some("AXSheet");
+                    CFRelease(role);
+                }
+                if is_modal {
+                    break;
+                }
+            }
+            CFRelease(children);
+        }
+    }
+    CFRelease(focused);
+    is_modal
+}
+
+fn is_process_running(pid: i32) -> bool {
+    use objc2_app_kit::NSRunningApplication;
+
+    objc2::rc::autoreleasepool(|_pool| {
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+            .is_some_and(|app| !app.isTerminated())
+    })
 }
 
 pub fn close_window(window_id: u32) {
@@ -969,6 +1330,38 @@ pub fn close_window(window_id: u32) {
     }
 }
 
+/// `AXIsEdited` on the window, which AppKit sets for document-edited windows.
+pub fn window_has_unsaved_changes(window_id: u32) -> bool {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *const c_void,
+        ) -> i32;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFBooleanTrue: *const c_void;
+    }
+
+    let Some((pid, title)) = cg_window_pid_and_title(window_id) else {
+        return false;
+    };
+    let win = unsafe { ax_find_window(pid, window_id, &title) };
+    if win.is_null() {
+        return false;
+    }
+    unsafe {
+        let edited_attr = cg_helpers::cfstr(b"AXIsEdited");
+        let mut value: *const c_void = std::ptr::null();
+        let err = AXUIElementCopyAttributeValue(win, edited_attr, &mut value);
+        CFRelease(edited_attr as *const c_void);
+        CFRelease(win);
+        err == 0 && !value.is_null() && value == kCFBooleanTrue
+    }
+}
+
 pub fn quit_app(window_id: u32) {
     let Some((pid, _title)) = cg_window_pid_and_title(window_id) else {
         return;
@@ -981,6 +1374,45 @@ pub fn quit_app(window_id: u32) {
     });
 }
 
+/// Activate the owning app and send it Cmd+N, the usual "New Window" shortcut.
+pub fn open_new_window(window_id: u32, _app_name: &str) -> bool {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventCreateKeyboardEvent(source: *const c_void, keycode: u16, down: bool) -> *const c_void;
+        fn CGEventSetFlags(event: *const c_void, flags: u64);
+        fn CGEventPostToPid(pid: i32, event: *const c_void);
+    }
+    const K_VK_ANSI_N: u16 = 45;
+    const K_CG_EVENT_FLAG_MASK_COMMAND: u64 = 0x0010_0000;
+
+    let Some((pid, _title)) = cg_window_pid_and_title(window_id) else {
+        return false;
+    };
+    let activated = objc2::rc::autoreleasepool(|_pool| {
+        use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid).is_some_and(|app| {
+            #[allow(deprecated)]
+            app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps)
+        })
+    });
+    if !activated {
+        return false;
+    }
+    for down in [true, false] {
+        unsafe {
+            let event = CGEventCreateKeyboardEvent(std::ptr::null(), K_VK_ANSI_N, down);
+            if event.is_null() {
+                return false;
+            }
+            CGEventSetFlags(event, K_CG_EVENT_FLAG_MASK_COMMAND);
+            CGEventPostToPid(pid, event);
+            CFRelease(event);
+        }
+    }
+    true
+}
+
 pub fn minimize_window_by_id(window_id: u32) {
     let Some((pid, title)) = cg_window_pid_and_title(window_id) else {
         return;
@@ -1185,6 +1617,64 @@ unsafe fn ax_set_minimized(win_el: *const c_void) {
     CFRelease(minimized_attr as *const c_void);
 }
 
+pub fn move_window_to_monitor(window_id: u32, monitor: (f32, f32, f32, f32)) -> bool {
+    #[repr(C)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXValueCreate(value_type: u32, value: *const c_void) -> *const c_void;
+        fn AXUIElementSetAttributeValue(
+            el: *const c_void,
+            attr: *const c_void,
+            val: *const c_void,
+        ) -> i32;
+    }
+    const K_AX_VALUE_CG_POINT_TYPE: u32 = 1;
+
+    let list = unsafe {
+        CGWindowListCopyWindowInfo(K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS, K_CG_NULL_WINDOW_ID)
+    };
+    if list.is_null() {
+        return false;
+    }
+    let target = parse_cg_window_list(list, std::process::id() as i32)
+        .into_iter()
+        .find(|w| w.id == window_id);
+    unsafe { CFRelease(list as *const c_void) };
+    let Some(target) = target else {
+        return false;
+    };
+    let Some((x, y)) =
+        super::position_on_monitor((target.x, target.y, target.w, target.h), monitor)
+    else {
+        return false;
+    };
+
+    unsafe {
+        let win = ax_find_window(target.pid, window_id, &target.title);
+        if win.is_null() {
+            return false;
+        }
+        let point = CGPoint { x: x as f64, y: y as f64 };
+        let value = AXValueCreate(K_AX_VALUE_CG_POINT_TYPE, &point as *const CGPoint as *const c_void);
+        let position_attr = cg_helpers::cfstr(b"AXPosition");
+        let err = if value.is_null() {
+            -1
+        } else {
+            AXUIElementSetAttributeValue(win, position_attr, value)
+        };
+        CFRelease(position_attr as *const c_void);
+        if !value.is_null() {
+            CFRelease(value);
+        }
+        CFRelease(win);
+        err == 0
+    }
+}
+
 pub fn move_app_window(_title: &str, _x: i32, _y: i32) -> bool {
     // macOS GPUI windows can't be reliably repositioned via AX after creation.
     // Return false so the caller closes and recreates on the correct monitor.
@@ -1205,7 +1695,7 @@ pub fn dismiss_picker(_window: &mut gpui::Window) {
     let mtm = MainThreadMarker::new().expect("must be on main thread");
     let app = NSApplication::sharedApplication(mtm);
     for win in app.windows().iter() {
-        if win.title().to_string() == "qol-alt-tab-picker" {
+        if win.title().to_string() == super::PICKER_WINDOW_TITLE {
             win.orderOut(None);
             return;
         }
@@ -1223,9 +1713,30 @@ fn cg_event_flags() -> u64 {
     unsafe { CGEventSourceFlagsState(K_CG_EVENT_SOURCE_STATE_COMBINED) }
 }
 
-pub fn is_modifier_held() -> bool {
-    const K_CG_EVENT_FLAG_MASK_ALTERNATE: u64 = 0x0008_0000;
-    cg_event_flags() & K_CG_EVENT_FLAG_MASK_ALTERNATE != 0
+pub fn cursor_position() -> Option<(f32, f32)> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventCreate(source: *const c_void) -> *const c_void;
+        fn CGEventGetLocation(event: *const c_void) -> CGPoint;
+    }
+
+    let event = unsafe { CGEventCreate(std::ptr::null()) };
+    if event.is_null() {
+        return None;
+    }
+    let location = unsafe { CGEventGetLocation(event) };
+    unsafe { CFRelease(event) };
+    Some((location.x as f32, location.y as f32))
+}
+
+pub fn is_modifier_held(modifier: Modifier) -> bool {
+    let mask: u64 = match modifier {
+        Modifier::Alt => 0x0008_0000,     // kCGEventFlagMaskAlternate
+        Modifier::Control => 0x0004_0000, // kCGEventFlagMaskControl
+        Modifier::Command => 0x0010_0000, // kCGEventFlagMaskCommand
+        Modifier::Shift => 0x0002_0000,   // kCGEventFlagMaskShift
+    };
+    cg_event_flags() & mask != 0
 }
 
 pub fn is_shift_held() -> bool {
@@ -1233,6 +1744,42 @@ pub fn is_shift_held() -> bool {
     cg_event_flags() & K_CG_EVENT_FLAG_MASK_SHIFT != 0
 }
 
+pub fn set_window_always_on_top(title: &str, on_top: bool) {
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::MainThreadMarker;
+
+    // NSNormalWindowLevel = 0, NSFloatingWindowLevel = 3.
+    let level = if on_top { 3 } else { 0 };
+    let mtm = MainThreadMarker::new().expect("must be on main thread");
+    let app = NSApplication::sharedApplication(mtm);
+    for win in app.windows().iter() {
+        if win.title().to_string() == title {
+            win.setLevel(level);
+        }
+    }
+}
+
+pub fn prefers_reduced_motion() -> bool {
+    objc2::rc::autoreleasepool(|_pool| {
+        use objc2_app_kit::NSWorkspace;
+
+        NSWorkspace::sharedWorkspace().accessibilityDisplayShouldReduceMotion()
+    })
+}
+
+pub fn system_accent_color() -> Option<u32> {
+    use objc2_app_kit::{NSColor, NSColorSpace};
+
+    objc2::rc::autoreleasepool(|_pool| {
+        let srgb = unsafe {
+            NSColor::controlAccentColor().colorUsingColorSpace(&NSColorSpace::sRGBColorSpace())
+        }?;
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u32;
+        let (r, g, b) = unsafe { (srgb.redComponent(), srgb.greenComponent(), srgb.blueComponent()) };
+        Some((channel(r) << 16) | (channel(g) << 8) | channel(b))
+    })
+}
+
 pub fn disable_window_shadow() {
     use objc2_app_kit::{NSApplication, NSColor};
     use objc2_foundation::MainThreadMarker;
@@ -1,8 +1,24 @@
 #[cfg(target_os = "macos")]
 pub(crate) mod cg_helpers;
+mod sck;
+mod desktop_entry;
+mod wayland;
 
 pub use qol_plugin_api::app_icon::RgbaImage;
 
+use crate::config::{MinimizedActivate, Modifier, PreviewQuality};
+
+/// Title set on the picker window; used to find (and skip) it by name.
+pub const PICKER_WINDOW_TITLE: &str = "qol-alt-tab-picker";
+
+/// Wayland app id of the picker window, so the toplevel list can skip it.
+pub const PICKER_APP_ID: &str = "qol-alt-tab";
+
+/// Whether the daemon needs a hidden window to stay alive between pickers.
+/// GPUI's Linux and Windows backends stop the event loop once the last window
+/// closes; on macOS NSApplication keeps running with no windows.
+pub const KEEPALIVE_REQUIRED: bool = !cfg!(target_os = "macos");
+
 #[derive(Debug, Clone)]
 pub struct WindowInfo {
     pub id: u32,
@@ -15,6 +31,20 @@ pub struct WindowInfo {
     pub width: f32,
     pub height: f32,
     pub is_minimized: bool,
+    /// macOS: window lives on another Space (not in the on-screen CG list).
+    pub is_off_space: bool,
+    /// macOS: the window's document has unsaved edits (dot in its close button).
+    pub has_unsaved_changes: bool,
+}
+
+/// Outcome of [`activate_window`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    Activated,
+    /// The window or its owning process no longer exists.
+    Gone,
+    /// The window exists but was not brought forward, e.g. a modal is up.
+    Refused,
 }
 
 #[cfg(target_os = "linux")]
@@ -34,28 +64,79 @@ use windows as imp;
 #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
 compile_error!("plugin-alt-tab: unsupported target OS; add src/platform/<os>.rs and wire it in src/platform/mod.rs");
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
-    imp::get_open_windows()
+/// `include_other_spaces` (macOS only) also lists windows on other Spaces.
+pub fn get_open_windows(include_other_spaces: bool, include_desktop: bool) -> Vec<WindowInfo> {
+    imp::get_open_windows(include_other_spaces, include_desktop)
+}
+
+pub fn get_on_screen_windows(include_desktop: bool) -> Vec<WindowInfo> {
+    imp::get_on_screen_windows(include_desktop)
 }
 
-pub fn get_on_screen_windows() -> Vec<WindowInfo> {
-    imp::get_on_screen_windows()
+/// Whether the window list changed since the last call, from a platform event
+/// subscription. `None` when no subscription is available; callers poll instead.
+pub fn window_list_changed() -> Option<bool> {
+    imp::window_list_changed()
 }
 
-pub fn capture_previews_cg(targets: &[(usize, u32)], max_w: usize, max_h: usize) -> Vec<(usize, Option<RgbaImage>)> {
-    imp::capture_previews_cg(targets, max_w, max_h)
+pub fn capture_previews_cg(
+    targets: &[(usize, u32)],
+    max_w: usize,
+    max_h: usize,
+    quality: PreviewQuality,
+    capture_cursor: bool,
+    screen_capture_kit: bool,
+) -> Vec<(usize, Option<RgbaImage>)> {
+    imp::capture_previews_cg(targets, max_w, max_h, quality, capture_cursor, screen_capture_kit)
 }
 
-pub fn activate_window(window_id: u32) {
-    imp::activate_window(window_id)
+/// Brings the window forward, un-minimizing it if needed; `restore` picks how
+/// a minimized window comes back.
+pub fn activate_window(window_id: u32, restore: MinimizedActivate) -> Activation {
+    imp::activate_window(window_id, restore)
+}
+
+/// Move a window onto `monitor` (x, y, width, height), keeping its size.
+/// Returns false if the window was already there or could not be moved.
+pub fn move_window_to_monitor(window_id: u32, monitor: (f32, f32, f32, f32)) -> bool {
+    imp::move_window_to_monitor(window_id, monitor)
+}
+
+/// Whether the center of `window` (x, y, width, height) lies on `monitor`.
+pub(crate) fn center_on_monitor(window: (f32, f32, f32, f32), monitor: (f32, f32, f32, f32)) -> bool {
+    let (wx, wy, ww, wh) = window;
+    let (mx, my, mw, mh) = monitor;
+    let (cx, cy) = (wx + ww / 2.0, wy + wh / 2.0);
+    cx >= mx && cx < mx + mw && cy >= my && cy < my + mh
+}
+
+/// Top-left position that centers a `w`x`h` window on `monitor`, or None when the
+/// window's center already lies on it.
+pub(crate) fn position_on_monitor(
+    window: (f32, f32, f32, f32),
+    monitor: (f32, f32, f32, f32),
+) -> Option<(i32, i32)> {
+    let (_, _, ww, wh) = window;
+    let (mx, my, mw, mh) = monitor;
+    if center_on_monitor(window, monitor) {
+        return None;
+    }
+    let x = mx + ((mw - ww) / 2.0).max(0.0);
+    let y = my + ((mh - wh) / 2.0).max(0.0);
+    Some((x as i32, y as i32))
 }
 
 pub fn move_app_window(title: &str, x: i32, y: i32) -> bool {
     imp::move_app_window(title, x, y)
 }
 
-pub fn is_modifier_held() -> bool {
-    imp::is_modifier_held()
+/// Global cursor position in the same coordinate space as monitor bounds.
+pub fn cursor_position() -> Option<(f32, f32)> {
+    imp::cursor_position()
+}
+
+pub fn is_modifier_held(modifier: Modifier) -> bool {
+    imp::is_modifier_held(modifier)
 }
 
 pub fn is_shift_held() -> bool {
@@ -74,6 +155,44 @@ pub fn get_app_icons(windows: &[WindowInfo]) -> std::collections::HashMap<String
     imp::get_app_icons(windows)
 }
 
+/// Dock badge text (e.g. unread counts) keyed by app name. Empty where the
+/// platform has no badge concept.
+pub fn get_app_badges() -> std::collections::HashMap<String, String> {
+    imp::get_app_badges()
+}
+
+/// Platform prerequisites for `--doctor`: (description, satisfied).
+pub fn environment_checks() -> Vec<(&'static str, bool)> {
+    imp::environment_checks()
+}
+
+/// Name of the app that has focus, matched against `WindowInfo::app_name`.
+/// Must be called before the picker takes focus.
+pub fn frontmost_app_name() -> Option<String> {
+    imp::frontmost_app_name()
+}
+
+/// True when the frontmost window is a modal dialog (or has a sheet attached).
+/// Must be called before the picker takes focus.
+pub fn frontmost_has_modal() -> bool {
+    imp::frontmost_has_modal()
+}
+
+/// Keep the picker window above normal windows (or drop it back to normal level).
+pub fn set_window_always_on_top(title: &str, on_top: bool) {
+    imp::set_window_always_on_top(title, on_top)
+}
+
+/// The OS accent color as `0xRRGGBB`, if the desktop exposes one.
+pub fn system_accent_color() -> Option<u32> {
+    imp::system_accent_color()
+}
+
+/// The user asked the OS to minimize animations.
+pub fn prefers_reduced_motion() -> bool {
+    imp::prefers_reduced_motion()
+}
+
 pub fn disable_window_shadow() {
     imp::disable_window_shadow()
 }
@@ -82,10 +201,22 @@ pub fn close_window(window_id: u32) {
     imp::close_window(window_id)
 }
 
+/// Whether closing the window could lose unsaved work. Always false where the
+/// platform doesn't expose it.
+pub fn window_has_unsaved_changes(window_id: u32) -> bool {
+    imp::window_has_unsaved_changes(window_id)
+}
+
 pub fn quit_app(window_id: u32) {
     imp::quit_app(window_id)
 }
 
+/// Best effort: ask the app owning `window_id` for another window. Depends on
+/// the app honouring Cmd+N (macOS) or opening a window when launched again (Linux).
+pub fn open_new_window(window_id: u32, app_name: &str) -> bool {
+    imp::open_new_window(window_id, app_name)
+}
+
 pub fn minimize_window_by_id(window_id: u32) {
     imp::minimize_window_by_id(window_id)
 }
@@ -0,0 +1,149 @@
+//! ScreenCaptureKit window thumbnails for macOS 14+, where
+//! `CGWindowListCreateImage` is deprecated. Used only with
+//! `display.screen_capture_kit`. Only compiled on macOS.
+
+#![cfg(target_os = "macos")]
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+use objc2::AllocAnyThread;
+use objc2_core_graphics::CGImage;
+use objc2_foundation::{NSError, NSOperatingSystemVersion, NSProcessInfo};
+use objc2_screen_capture_kit::{
+    SCContentFilter, SCScreenshotManager, SCShareableContent, SCStreamConfiguration, SCWindow,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Upper bound on waiting for ScreenCaptureKit's completion handlers.
+const SCK_TIMEOUT: Duration = Duration::from_millis(500);
+/// `kCVPixelFormatType_32BGRA`, the layout the CG path produces.
+const PIXEL_FORMAT_BGRA: u32 = u32::from_be_bytes(*b"BGRA");
+/// Captures are requested at this multiple of the thumbnail size so
+/// `PreviewQuality` still decides the final downscale.
+const OVERSAMPLE: f64 = 2.0;
+/// Cached shareable content is requeried after this long, since window
+/// frames (which size each capture) drift as windows move and resize.
+const CONTENT_MAX_AGE: Duration = Duration::from_secs(2);
+
+/// `SCScreenshotManager` needs macOS 14.
+fn available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let sonoma = NSOperatingSystemVersion { majorVersion: 14, minorVersion: 0, patchVersion: 0 };
+        NSProcessInfo::processInfo().isOperatingSystemAtLeastVersion(sonoma)
+    })
+}
+
+/// SCK hands back immutable snapshots; moving one off its callback queue is fine.
+struct Snapshot<T>(T);
+unsafe impl<T> Send for Snapshot<T> {}
+
+/// Shareable windows keyed by CG window id. None if SCK fails or times out,
+/// e.g. when screen recording permission has not been granted.
+fn shareable_windows() -> Option<HashMap<u32, Retained<SCWindow>>> {
+    let (tx, rx) = mpsc::channel();
+    let handler = RcBlock::new(move |content: *mut SCShareableContent, _error: *mut NSError| {
+        let _ = tx.send(Snapshot(unsafe { Retained::retain(content) }));
+    });
+    unsafe { SCShareableContent::getShareableContentWithCompletionHandler(&handler) };
+    let content = rx.recv_timeout(SCK_TIMEOUT).ok()?.0?;
+    let windows = unsafe { content.windows() };
+    Some(windows.iter().map(|w| (unsafe { w.windowID() }, w)).collect())
+}
+
+/// Last shareable content, when it was queried, and every window id it
+/// answered for (listed or requested), so ids SCK never lists don't force a requery.
+struct Content {
+    queried_at: Instant,
+    windows: HashMap<u32, Retained<SCWindow>>,
+    known: HashSet<u32>,
+}
+
+static CONTENT: Mutex<Option<Snapshot<Content>>> = Mutex::new(None);
+
+/// Shareable windows for `targets`, reusing the last query until it ages out
+/// or a target appears that it has not seen (the window list changed).
+fn cached_windows(targets: &[(usize, u32)]) -> Option<HashMap<u32, Retained<SCWindow>>> {
+    let mut cache = CONTENT.lock().ok()?;
+    let fresh = cache.as_ref().is_some_and(|Snapshot(c)| {
+        c.queried_at.elapsed() < CONTENT_MAX_AGE && targets.iter().all(|(_, id)| c.known.contains(id))
+    });
+    if !fresh {
+        let windows = shareable_windows()?;
+        let known = windows.keys().copied().chain(targets.iter().map(|&(_, id)| id)).collect();
+        *cache = Some(Snapshot(Content { queried_at: Instant::now(), windows, known }));
+    }
+    cache.as_ref().map(|Snapshot(c)| c.windows.clone())
+}
+
+fn configuration(window: &SCWindow, max_w: usize, max_h: usize) -> Retained<SCStreamConfiguration> {
+    let frame = unsafe { window.frame() };
+    let (w, h) = (frame.size.width.max(1.0), frame.size.height.max(1.0));
+    let fit = (max_w as f64 / w).min(max_h as f64 / h) * OVERSAMPLE;
+    let config = unsafe { SCStreamConfiguration::new() };
+    unsafe {
+        config.setWidth(((w * fit).round() as usize).max(1));
+        config.setHeight(((h * fit).round() as usize).max(1));
+        config.setPixelFormat(PIXEL_FORMAT_BGRA);
+        config.setShowsCursor(false);
+    }
+    config
+}
+
+/// Capture `targets` (index, CG window id) with one-shot screenshots. `convert`
+/// runs on SCK's callback queue and must copy the pixels out before the image
+/// is released. Returns None when SCK is unavailable so the caller falls back
+/// to CoreGraphics; windows SCK doesn't know or doesn't answer in time get None.
+pub(super) fn capture_windows<T: Send + 'static>(
+    targets: &[(usize, u32)],
+    max_w: usize,
+    max_h: usize,
+    convert: impl Fn(u32, &CGImage) -> Option<T> + Send + Sync + 'static,
+) -> Option<Vec<(usize, Option<T>)>> {
+    if !available() {
+        return None;
+    }
+    let windows = cached_windows(targets)?;
+    let convert = Arc::new(convert);
+    let (tx, rx) = mpsc::channel();
+    let mut results = Vec::with_capacity(targets.len());
+    let mut pending = HashSet::new();
+    for &(idx, wid) in targets {
+        let Some(window) = windows.get(&wid) else {
+            results.push((idx, None));
+            continue;
+        };
+        let filter = unsafe {
+            SCContentFilter::initWithDesktopIndependentWindow(SCContentFilter::alloc(), window)
+        };
+        let config = configuration(window, max_w, max_h);
+        let convert = convert.clone();
+        let tx = tx.clone();
+        let handler = RcBlock::new(move |image: *mut CGImage, _error: *mut NSError| {
+            let pixels = unsafe { image.as_ref() }.and_then(|image| convert(wid, image));
+            let _ = tx.send((idx, pixels));
+        });
+        unsafe {
+            SCScreenshotManager::captureImageWithFilter_configuration_completionHandler(
+                &filter,
+                &config,
+                Some(&handler),
+            )
+        };
+        pending.insert(idx);
+    }
+    drop(tx);
+    let deadline = Instant::now() + SCK_TIMEOUT;
+    while !pending.is_empty() {
+        let Ok((idx, pixels)) = rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        else {
+            break;
+        };
+        pending.remove(&idx);
+        results.push((idx, pixels));
+    }
+    results.extend(pending.into_iter().map(|idx| (idx, None)));
+    Some(results)
+}
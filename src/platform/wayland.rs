@@ -0,0 +1,234 @@
+//! Window list for Wayland sessions without Xwayland, via the compositor's
+//! `wlr-foreign-toplevel-management` protocol (wlroots compositors, KWin).
+//! Toplevels carry a title, app id and state but no geometry or pixels, so
+//! these windows get icons and placeholders rather than previews. Only
+//! compiled on Linux.
+
+#![cfg(target_os = "linux")]
+
+use super::WindowInfo;
+use std::sync::{Mutex, OnceLock};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{event_created_child, Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+/// Highest manager version we understand.
+const MANAGER_VERSION: u32 = 3;
+
+#[derive(Default)]
+struct Toplevel {
+    title: String,
+    app_id: String,
+    minimized: bool,
+    activated: bool,
+    closed: bool,
+}
+
+#[derive(Default)]
+struct State {
+    manager: Option<ZwlrForeignToplevelManagerV1>,
+    seat: Option<wl_seat::WlSeat>,
+    /// In announcement order; the compositor reports no stacking.
+    toplevels: Vec<(ZwlrForeignToplevelHandleV1, Toplevel)>,
+}
+
+/// One connection for the daemon's lifetime so window ids (protocol object
+/// ids) stay stable between enumerations. None when there is no Wayland
+/// display or the compositor lacks the protocol.
+static SESSION: OnceLock<Option<Mutex<(EventQueue<State>, State)>>> = OnceLock::new();
+
+fn session() -> Option<&'static Mutex<(EventQueue<State>, State)>> {
+    SESSION
+        .get_or_init(|| {
+            let conn = Connection::connect_to_env().ok()?;
+            let mut queue = conn.new_event_queue();
+            let qh = queue.handle();
+            conn.display().get_registry(&qh, ());
+            let mut state = State::default();
+            // Globals, then the initial toplevel announcements and their details.
+            for _ in 0..3 {
+                queue.roundtrip(&mut state).ok()?;
+            }
+            state.manager.as_ref()?;
+            Some(Mutex::new((queue, state)))
+        })
+        .as_ref()
+}
+
+/// Whether the compositor offers foreign-toplevel management.
+pub(super) fn available() -> bool {
+    session().is_some()
+}
+
+/// Current toplevels, activated one first, then newest first. None without
+/// a usable Wayland session.
+pub(super) fn toplevels() -> Option<Vec<WindowInfo>> {
+    let mut guard = session()?.lock().ok()?;
+    let (queue, state) = &mut *guard;
+    queue.roundtrip(state).ok()?;
+    state.toplevels.retain(|(_, t)| !t.closed);
+    let mut windows: Vec<(bool, WindowInfo)> = state
+        .toplevels
+        .iter()
+        .rev()
+        .filter(|(_, t)| !is_own(t))
+        .map(|(handle, t)| {
+            let window = WindowInfo {
+                id: handle.id().protocol_id(),
+                title: t.title.clone(),
+                app_name: t.app_id.clone(),
+                preview_path: None,
+                icon: None,
+                x: 0.0,
+                y: 0.0,
+                width: 0.0,
+                height: 0.0,
+                is_minimized: t.minimized,
+                is_off_space: false,
+                has_unsaved_changes: false,
+            };
+            (t.activated, window)
+        })
+        .collect();
+    windows.sort_by_key(|(activated, _)| !activated);
+    Some(windows.into_iter().map(|(_, w)| w).collect())
+}
+
+/// Our picker, or the keepalive window, which has neither title nor app id.
+fn is_own(t: &Toplevel) -> bool {
+    t.title == super::PICKER_WINDOW_TITLE
+        || t.app_id == super::PICKER_APP_ID
+        || (t.title.is_empty() && t.app_id.is_empty())
+}
+
+/// App id of the toplevel the compositor reports as activated, if any.
+pub(super) fn activated_app_id() -> Option<String> {
+    let mut guard = session()?.lock().ok()?;
+    let (queue, state) = &mut *guard;
+    queue.roundtrip(state).ok()?;
+    state
+        .toplevels
+        .iter()
+        .find(|(_, t)| t.activated && !t.closed && !is_own(t))
+        .map(|(_, t)| t.app_id.clone())
+}
+
+/// Ask the compositor to focus (and unminimize) toplevel `id`.
+/// Gone only when the compositor no longer lists the toplevel.
+pub(super) fn activate(id: u32) -> super::Activation {
+    use super::Activation;
+    let Some(session) = session() else {
+        return Activation::Refused;
+    };
+    let Ok(mut guard) = session.lock() else {
+        return Activation::Refused;
+    };
+    let (queue, state) = &mut *guard;
+    let Some((handle, toplevel)) = state
+        .toplevels
+        .iter()
+        .find(|(h, t)| h.id().protocol_id() == id && !t.closed)
+    else {
+        return Activation::Gone;
+    };
+    let Some(seat) = state.seat.clone() else {
+        return Activation::Refused;
+    };
+    if toplevel.minimized {
+        handle.unset_minimized();
+    }
+    handle.activate(&seat);
+    if queue.flush().is_ok() {
+        Activation::Activated
+    } else {
+        Activation::Refused
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for State {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let wl_registry::Event::Global { name, interface, version } = event else {
+            return;
+        };
+        if interface == ZwlrForeignToplevelManagerV1::interface().name {
+            state.manager = Some(registry.bind(name, version.min(MANAGER_VERSION), qh, ()));
+        } else if interface == wl_seat::WlSeat::interface().name && state.seat.is_none() {
+            state.seat = Some(registry.bind(name, 1, qh, ()));
+        }
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.push((toplevel, Toplevel::default()));
+        }
+    }
+
+    event_created_child!(State, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some((_, toplevel)) = state.toplevels.iter_mut().find(|(h, _)| h == handle) else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => toplevel.title = title,
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => toplevel.app_id = app_id,
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: raw } => {
+                // An array of native-endian u32 state values.
+                let states: Vec<u32> = raw
+                    .chunks_exact(4)
+                    .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                let has = |s: zwlr_foreign_toplevel_handle_v1::State| states.contains(&(s as u32));
+                toplevel.minimized = has(zwlr_foreign_toplevel_handle_v1::State::Minimized);
+                toplevel.activated = has(zwlr_foreign_toplevel_handle_v1::State::Activated);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                toplevel.closed = true;
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,22 +1,35 @@
 use super::WindowInfo;
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
+pub fn get_open_windows(_include_other_spaces: bool, _include_desktop: bool) -> Vec<WindowInfo> {
     Vec::new()
 }
 
-pub fn get_on_screen_windows() -> Vec<WindowInfo> {
+pub fn get_on_screen_windows(_include_desktop: bool) -> Vec<WindowInfo> {
     Vec::new()
 }
 
+pub fn window_list_changed() -> Option<bool> {
+    None
+}
+
 pub fn capture_previews_cg(
     _targets: &[(usize, u32)],
     _max_w: usize,
     _max_h: usize,
+    _quality: crate::config::PreviewQuality,
+    _capture_cursor: bool,
+    _screen_capture_kit: bool,
 ) -> Vec<(usize, Option<super::RgbaImage>)> {
     Vec::new()
 }
 
-pub fn activate_window(_window_id: u32) {}
+pub fn activate_window(_window_id: u32, _restore: crate::config::MinimizedActivate) -> super::Activation {
+    super::Activation::Refused
+}
+
+pub fn move_window_to_monitor(_window_id: u32, _monitor: (f32, f32, f32, f32)) -> bool {
+    false
+}
 
 pub fn move_app_window(_title: &str, _x: i32, _y: i32) -> bool {
     false
@@ -30,7 +43,11 @@ pub fn dismiss_picker(window: &mut gpui::Window) {
     window.minimize_window();
 }
 
-pub fn is_modifier_held() -> bool {
+pub fn cursor_position() -> Option<(f32, f32)> {
+    None
+}
+
+pub fn is_modifier_held(_modifier: crate::config::Modifier) -> bool {
     false
 }
 
@@ -38,14 +55,48 @@ pub fn is_shift_held() -> bool {
     false
 }
 
+pub fn environment_checks() -> Vec<(&'static str, bool)> {
+    Vec::new()
+}
+
+pub fn frontmost_app_name() -> Option<String> {
+    None
+}
+
+pub fn frontmost_has_modal() -> bool {
+    false
+}
+
+pub fn set_window_always_on_top(_title: &str, _on_top: bool) {}
+
+pub fn system_accent_color() -> Option<u32> {
+    None
+}
+
+pub fn prefers_reduced_motion() -> bool {
+    false
+}
+
 pub fn disable_window_shadow() {}
 
+pub fn get_app_badges() -> std::collections::HashMap<String, String> {
+    std::collections::HashMap::new()
+}
+
 pub fn get_app_icons(_windows: &[WindowInfo]) -> std::collections::HashMap<String, super::RgbaImage> {
     std::collections::HashMap::new()
 }
 
 pub fn close_window(_window_id: u32) {}
 
+pub fn window_has_unsaved_changes(_window_id: u32) -> bool {
+    false
+}
+
 pub fn quit_app(_window_id: u32) {}
 
+pub fn open_new_window(_window_id: u32, _app_name: &str) -> bool {
+    false
+}
+
 pub fn minimize_window_by_id(_window_id: u32) {}
@@ -1,5 +1,15 @@
+use crate::config::PreviewQuality;
+use crate::private_files;
 use gpui::RenderImage;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Previews by window id with the time each was captured, so a preview
+/// carried over from the prewarm keeps its real age.
+pub(crate) type TimedPreviews = HashMap<u32, (Arc<RenderImage>, Instant)>;
 
 /// Sample ~1KB of evenly-spaced pixels for a fast content-change check.
 pub(crate) fn fast_pixel_hash(data: &[u8]) -> u64 {
@@ -15,9 +25,264 @@ pub(crate) fn fast_pixel_hash(data: &[u8]) -> u64 {
     hasher.finish()
 }
 
+/// None when `data` is not exactly `w`x`h` 4-byte pixels, so the card keeps
+/// its placeholder instead of showing a garbled or empty frame.
 pub(crate) fn bgra_to_render_image(data: &[u8], w: usize, h: usize) -> Option<Arc<RenderImage>> {
+    if !dimensions_match(data.len(), w, h) {
+        eprintln!(
+            "[alt-tab/preview] frame size mismatch: {} bytes for {}x{} (expected {})",
+            data.len(),
+            w,
+            h,
+            w.saturating_mul(h).saturating_mul(4)
+        );
+        return None;
+    }
     let buf =
         image::ImageBuffer::<image::Rgba<u8>, Vec<u8>>::from_raw(w as u32, h as u32, data.to_vec())?;
     let frame = image::Frame::new(buf);
     Some(Arc::new(RenderImage::new(smallvec::smallvec![frame])))
 }
+
+/// `placeholder_image` decoded once per configured path. The config is
+/// reloaded on every open, so the result (or failure) is kept and reused.
+pub(crate) fn placeholder_image(path: Option<PathBuf>) -> Option<Arc<RenderImage>> {
+    static DECODED: Mutex<Option<(PathBuf, Option<Arc<RenderImage>>)>> = Mutex::new(None);
+    let path = path?;
+    let mut decoded = DECODED.lock().ok()?;
+    if let Some((_, image)) = decoded.as_ref().filter(|(cached, _)| *cached == path) {
+        return image.clone();
+    }
+    let image = match image::open(&path) {
+        Ok(image) => {
+            let mut rgba = image.into_rgba8();
+            let (w, h) = rgba.dimensions();
+            // RenderImage frames are BGRA.
+            for px in rgba.pixels_mut() {
+                px.0.swap(0, 2);
+            }
+            bgra_to_render_image(rgba.as_raw(), w as usize, h as usize)
+        }
+        Err(e) => {
+            eprintln!(
+                "[alt-tab] placeholder_image '{}' could not be loaded ({}) — using the default",
+                path.display(),
+                e
+            );
+            None
+        }
+    };
+    *decoded = Some((path, image.clone()));
+    image
+}
+
+/// Approximate memory held by a preview: its first frame at 4 bytes per pixel.
+pub(crate) fn render_image_bytes(image: &RenderImage) -> usize {
+    let size = image.size(0);
+    size.width.0.max(0) as usize * size.height.0.max(0) as usize * 4
+}
+
+/// Ids to drop so the rest fit in `budget` bytes. `entries` (id, bytes) are
+/// ordered most worth keeping first; everything after the first entry that
+/// would overflow goes. The first entry is always kept.
+pub(crate) fn over_budget(entries: &[(u32, usize)], budget: usize) -> Vec<u32> {
+    let mut total = 0usize;
+    let mut cut = entries.len();
+    for (i, (_, bytes)) in entries.iter().enumerate() {
+        total = total.saturating_add(*bytes);
+        if total > budget && i > 0 {
+            cut = i;
+            break;
+        }
+    }
+    entries[cut..].iter().map(|(id, _)| *id).collect()
+}
+
+/// `previews/<pid>`: each daemon spills into (and clears) only its own
+/// directory, so concurrent daemons never touch each other's files.
+fn own_spill_dir() -> Option<PathBuf> {
+    Some(private_files::dir("previews")?.join(std::process::id().to_string()))
+}
+
+fn spill_dir() -> Option<PathBuf> {
+    private_files::ensure_dir(&own_spill_dir()?).ok()
+}
+
+fn spill_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{}.bgra", id))
+}
+
+/// Write previews dropped for the memory budget to disk, off the UI thread,
+/// so a card scrolled back into view gets its last frame back at once.
+/// Layout: width and height as little-endian u32, then the BGRA pixels.
+pub(crate) fn spill(previews: Vec<(u32, Arc<RenderImage>)>) {
+    if previews.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let Some(dir) = spill_dir() else {
+            return;
+        };
+        for (id, image) in previews {
+            let size = image.size(0);
+            let Some(pixels) = image.as_bytes(0) else {
+                continue;
+            };
+            let path = spill_path(&dir, id);
+            let _ = std::fs::remove_file(&path);
+            let written = private_files::create(&path).and_then(|mut file| {
+                file.write_all(&(size.width.0.max(0) as u32).to_le_bytes())?;
+                file.write_all(&(size.height.0.max(0) as u32).to_le_bytes())?;
+                file.write_all(pixels)
+            });
+            if let Err(e) = written {
+                eprintln!("[alt-tab/preview] failed to spill preview {}: {}", id, e);
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    });
+}
+
+/// A preview written by `spill`, if it is there and intact.
+pub(crate) fn load_spilled(id: u32) -> Option<Arc<RenderImage>> {
+    let data = std::fs::read(spill_path(&spill_dir()?, id)).ok()?;
+    if data.len() < 8 {
+        return None;
+    }
+    let (header, pixels) = data.split_at(8);
+    let w = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let h = u32::from_le_bytes(header[4..].try_into().ok()?) as usize;
+    bgra_to_render_image(pixels, w, h)
+}
+
+/// Drop this daemon's spilled previews: at start (left by an earlier process
+/// with the same PID) and at exit.
+pub(crate) fn clear_spilled() {
+    if let Some(dir) = own_spill_dir() {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+/// Whether `len` bytes hold exactly `w`x`h` 4-byte pixels (and are non-empty).
+pub(crate) fn dimensions_match(len: usize, w: usize, h: usize) -> bool {
+    w > 0 && h > 0 && w.checked_mul(h).and_then(|n| n.checked_mul(4)) == Some(len)
+}
+
+/// Downscale a 4-byte-per-pixel source (rows `bytes_per_row` apart) to `dst_w`x`dst_h`.
+/// Channel order is preserved, so BGRA in means BGRA out.
+pub(crate) fn downscale_4bpp(
+    raw: &[u8],
+    src_w: usize,
+    src_h: usize,
+    bytes_per_row: usize,
+    dst_w: usize,
+    dst_h: usize,
+    quality: PreviewQuality,
+) -> Vec<u8> {
+    if quality == PreviewQuality::Fast {
+        return downscale_nearest(raw, src_w, src_h, bytes_per_row, dst_w, dst_h);
+    }
+    // Filtered paths need a tightly packed buffer; fall back to nearest if rows are short.
+    let Some(buf) = pack_rows(raw, src_w, src_h, bytes_per_row) else {
+        return downscale_nearest(raw, src_w, src_h, bytes_per_row, dst_w, dst_h);
+    };
+    let scaled = match quality {
+        PreviewQuality::Balanced => image::imageops::thumbnail(&buf, dst_w as u32, dst_h as u32),
+        _ => image::imageops::resize(
+            &buf,
+            dst_w as u32,
+            dst_h as u32,
+            image::imageops::FilterType::Lanczos3,
+        ),
+    };
+    scaled.into_raw()
+}
+
+/// Draw a small cursor dot (white with a dark ring) centered at `(x, y)` into a
+/// 4-byte-per-pixel buffer. The colors are channel-order agnostic.
+pub(crate) fn draw_cursor_marker(data: &mut [u8], w: usize, h: usize, x: f32, y: f32) {
+    const OUTER: f32 = 4.0;
+    const INNER: f32 = 2.5;
+    let x0 = (x - OUTER).floor().max(0.0) as usize;
+    let y0 = (y - OUTER).floor().max(0.0) as usize;
+    let x1 = ((x + OUTER).ceil() as usize).min(w);
+    let y1 = ((y + OUTER).ceil() as usize).min(h);
+    for py in y0..y1 {
+        for px in x0..x1 {
+            let d = ((px as f32 + 0.5 - x).powi(2) + (py as f32 + 0.5 - y).powi(2)).sqrt();
+            let shade = if d <= INNER {
+                0xff
+            } else if d <= OUTER {
+                0x10
+            } else {
+                continue;
+            };
+            let off = (py * w + px) * 4;
+            data[off..off + 3].fill(shade);
+            data[off + 3] = 0xff;
+        }
+    }
+}
+
+fn pack_rows(
+    raw: &[u8],
+    src_w: usize,
+    src_h: usize,
+    bytes_per_row: usize,
+) -> Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    let row_len = src_w * 4;
+    let mut packed = Vec::with_capacity(row_len * src_h);
+    for y in 0..src_h {
+        let start = y * bytes_per_row;
+        packed.extend_from_slice(raw.get(start..start + row_len)?);
+    }
+    image::ImageBuffer::from_raw(src_w as u32, src_h as u32, packed)
+}
+
+fn downscale_nearest(
+    raw: &[u8],
+    src_w: usize,
+    src_h: usize,
+    bytes_per_row: usize,
+    dst_w: usize,
+    dst_h: usize,
+) -> Vec<u8> {
+    let mut out = vec![0u8; dst_w * dst_h * 4];
+    for y in 0..dst_h {
+        let src_y = (y * src_h) / dst_h;
+        let row_start = src_y * bytes_per_row;
+        for x in 0..dst_w {
+            let src_x = (x * src_w) / dst_w;
+            let src_off = row_start + src_x * 4;
+            if src_off + 4 > raw.len() {
+                continue;
+            }
+            let dst_off = (y * dst_w + x) * 4;
+            out[dst_off..dst_off + 4].copy_from_slice(&raw[src_off..src_off + 4]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dimensions_match, over_budget};
+
+    #[test]
+    fn dimensions_must_cover_buffer_exactly() {
+        assert!(dimensions_match(2 * 3 * 4, 2, 3));
+        assert!(!dimensions_match(2 * 3 * 4 - 1, 2, 3));
+        assert!(!dimensions_match(2 * 3 * 4 + 4, 2, 3));
+        assert!(!dimensions_match(0, 0, 0));
+        assert!(!dimensions_match(4, usize::MAX, 2));
+    }
+
+    #[test]
+    fn budget_drops_the_tail_past_the_limit() {
+        let entries = [(1, 40), (2, 40), (3, 40), (4, 10)];
+        assert_eq!(over_budget(&entries, 100), vec![3, 4]);
+        assert_eq!(over_budget(&entries, 130), Vec::<u32>::new());
+        assert_eq!(over_budget(&entries, 0), vec![2, 3, 4]);
+        assert_eq!(over_budget(&[], 0), Vec::<u32>::new());
+    }
+}
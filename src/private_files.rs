@@ -0,0 +1,89 @@
+//! Owner-only directories and files for data other users must not read or
+//! plant: dump replies and spilled previews. Namespaced by QoL Tray install so
+//! concurrent installs keep their files apart.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `<$XDG_RUNTIME_DIR or the user cache dir>/qol-alt-tab/installs/<install id>/<name>`,
+/// or without the install part when no install id is set; never the shared
+/// temp dir, so other users can neither predict nor pre-create it.
+pub(crate) fn dir(name: &str) -> Option<PathBuf> {
+    let root = dirs::runtime_dir().or_else(dirs::cache_dir)?;
+    Some(scoped(root.join("qol-alt-tab"), install_id().as_deref(), name))
+}
+
+fn scoped(base: PathBuf, install_id: Option<&str>, name: &str) -> PathBuf {
+    match install_id {
+        Some(id) => base.join("installs").join(id).join(name),
+        None => base.join(name),
+    }
+}
+
+/// Active QoL Tray install, resolved like config discovery:
+/// `$QOL_TRAY_INSTALL_ID`, else `<data local dir>/qol-tray/active-install-id`.
+fn install_id() -> Option<String> {
+    let from_env = std::env::var("QOL_TRAY_INSTALL_ID").ok().and_then(|id| valid_install_id(&id));
+    from_env.or_else(|| {
+        let path = dirs::data_local_dir()?.join("qol-tray").join("active-install-id");
+        valid_install_id(&fs::read_to_string(path).ok()?)
+    })
+}
+
+/// `raw` trimmed, if it is safe as a single path component.
+fn valid_install_id(raw: &str) -> Option<String> {
+    let id = raw.trim();
+    let safe = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    safe.then(|| id.to_string())
+}
+
+/// `dir`, created 0700 if missing and canonicalized. Refused if it is a symlink.
+pub(crate) fn ensure_dir(dir: &Path) -> io::Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)?;
+    if !fs::symlink_metadata(dir)?.is_dir() {
+        return Err(io::Error::other(format!("{} is not a directory", dir.display())));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    dir.canonicalize()
+}
+
+/// A new owner-only file; fails if anything (a symlink included) is already there.
+pub(crate) fn create(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_id_scopes_the_directory() {
+        let base = PathBuf::from("/run/user/1000/qol-alt-tab");
+        assert_eq!(scoped(base.clone(), Some("work"), "previews"), base.join("installs/work/previews"));
+        assert_eq!(scoped(base.clone(), None, "previews"), base.join("previews"));
+    }
+
+    #[test]
+    fn install_id_must_be_one_path_component() {
+        assert_eq!(valid_install_id(" work-2\n").as_deref(), Some("work-2"));
+        assert_eq!(valid_install_id(""), None);
+        assert_eq!(valid_install_id(".."), None);
+        assert_eq!(valid_install_id("a/b"), None);
+    }
+}
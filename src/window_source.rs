@@ -1,17 +1,19 @@
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use std::sync::Arc;
 
+/// Preview, icon or placeholder filling its parent, which sets the size.
+/// Before the first preview: `placeholder_image`, else `placeholder_icon`, else "...".
 pub fn preview_tile(
     live_image: Option<&Arc<RenderImage>>,
     preview_path: &Option<String>,
     minimized_icon: Option<&Arc<RenderImage>>,
-    width: f32,
-    height: f32,
+    placeholder_icon: Option<&Arc<RenderImage>>,
+    placeholder_image: Option<&Arc<RenderImage>>,
 ) -> AnyElement {
     if let Some(icon) = minimized_icon {
         return div()
-            .w(px(width))
-            .h(px(height))
+            .size_full()
             .bg(rgb(0x1e2130))
             .rounded_md()
             .border_1()
@@ -29,22 +31,26 @@ pub fn preview_tile(
     }
     if let Some(render_image) = live_image {
         img(render_image.clone())
-            .w(px(width))
-            .h(px(height))
+            .size_full()
             .object_fit(ObjectFit::Fill)
             .rounded_md()
             .into_any_element()
     } else if let Some(path) = preview_path {
         img(std::path::PathBuf::from(path))
-            .w(px(width))
-            .h(px(height))
+            .size_full()
             .object_fit(ObjectFit::Fill)
             .rounded_md()
             .into_any_element()
+    } else if let Some(image) = placeholder_image {
+        img(image.clone())
+            .size_full()
+            .object_fit(ObjectFit::Cover)
+            .rounded_md()
+            .opacity(0.6)
+            .into_any_element()
     } else {
         div()
-            .w(px(width))
-            .h(px(height))
+            .size_full()
             .bg(rgb(0x1e2130))
             .rounded_md()
             .border_1()
@@ -54,7 +60,11 @@ pub fn preview_tile(
             .justify_center()
             .text_xs()
             .text_color(rgb(0x4a5268))
-            .child("...")
+            // Dimmed so it reads as "loading", unlike the minimized icon.
+            .map(|el| match placeholder_icon {
+                Some(icon) => el.child(img(icon.clone()).w(px(48.0)).h(px(48.0)).rounded_md().opacity(0.6)),
+                None => el.child("..."),
+            })
             .into_any_element()
     }
 }